@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::error::ErrorCode;
 use crate::state::*;
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -50,7 +53,7 @@ pub struct CreateJob<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 3455,
+        space = 8 + Job::LEN,
         seeds = [b"job", job_counter.count.to_le_bytes().as_ref()],
         bump
     )]
@@ -70,9 +73,92 @@ pub struct CreateJob<'info> {
             has_one = authority,
     )]
     pub user: Account<'info, User>,
+    
+    /// Payment mint for SPL jobs, omitted for native SOL
+    pub mint: Option<Account<'info, Mint>>,
+    /// CHECK: Escrow PDA, authority of the escrow token account
+    #[account(
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct UpdateJob<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    // Only the upgrade authority can claim the admin role
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()),
+    )]
+    pub program: Program<'info, crate::program::Backend>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::NotUpgradeAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::NotAdmin,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+#[derive(Accounts)]
 pub struct InitializeJobCounter<'info> {
     #[account(
         init,
@@ -89,6 +175,57 @@ pub struct InitializeJobCounter<'info> {
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
+pub struct InitializeJurorRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + JurorRegistry::LEN,
+        seeds = [b"juror_registry"],
+        bump
+    )]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+pub struct RegisterJuror<'info> {
+    #[account(
+        mut,
+        seeds = [b"juror_registry"],
+        bump = juror_registry.bump,
+    )]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", juror.key().as_ref()],
+        bump,
+    )]
+    pub juror_user: Account<'info, User>,
+    
+    pub juror: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+pub struct UnregisterJuror<'info> {
+    #[account(
+        mut,
+        seeds = [b"juror_registry"],
+        bump = juror_registry.bump,
+    )]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    
+    pub juror: Signer<'info>,
+}
+#[derive(Accounts)]
 #[instruction(job_id: u64)]
 pub struct SubmitProposal<'info> {
     #[account(
@@ -98,6 +235,15 @@ pub struct SubmitProposal<'info> {
     )]
     pub job: Account<'info, Job>,
     
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + Bid::LEN,
+        seeds = [b"bid", job.key().as_ref(), freelancer.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    
     #[account(
         mut,
         seeds = [b"user", freelancer.key().as_ref()],
@@ -107,27 +253,85 @@ pub struct SubmitProposal<'info> {
     
     #[account(mut)]
     pub freelancer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
-#[instruction(job_id: u64, freelancer: Pubkey, bid_amount: u64)] 
-pub struct AssignJob<'info> {
+#[instruction(job_id: u64)]
+pub struct UpdateProposal<'info> {
+    #[account(
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid", job.key().as_ref(), freelancer.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    
+    pub freelancer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct WithdrawProposal<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
         bump,
     )]
     pub job: Account<'info, Job>,
-    #[account(mut)]
-    pub client: Signer<'info>,
+    
     #[account(
         mut,
-        seeds = [b"user", client.key().as_ref()],
+        seeds = [b"bid", job.key().as_ref(), freelancer.key().as_ref()],
         bump,
-        constraint = client_user.authority == client.key()
+        close = freelancer
     )]
-    pub client_user: Account<'info, User>,
+    pub bid: Account<'info, Bid>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = user.authority == freelancer.key()
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64, freelancer: Pubkey, bid_amount: u64)] 
+pub struct AssignJob<'info> {
     #[account(
         mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        seeds = [b"bid", job.key().as_ref(), freelancer.as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
         seeds = [b"user", freelancer.as_ref()],
         bump,
         constraint = freelancer_user.authority == freelancer
@@ -140,12 +344,27 @@ pub struct AssignJob<'info> {
         bump
     )]
     pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Option<Program<'info, Token>>,
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
-pub struct SubmitWork<'info> {
+pub struct AcceptOffer<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
@@ -155,18 +374,46 @@ pub struct SubmitWork<'info> {
     
     #[account(
         mut,
+        seeds = [b"bid", job.key().as_ref(), freelancer.key().as_ref()],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+    
+    #[account(
         seeds = [b"user", freelancer.key().as_ref()],
         bump,
         constraint = freelancer_user.authority == freelancer.key()
     )]
     pub freelancer_user: Account<'info, User>,
     
+    #[account(
+        mut,
+        seeds = [b"user", job.client.as_ref()],
+        bump,
+        constraint = client_user.authority == job.client
+    )]
+    pub client_user: Account<'info, User>,
+    
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        space = 8 + Collaboration::LEN,
+        seeds = [
+            b"collab",
+            Collaboration::pair(&job.client, freelancer.key)[0].as_ref(),
+            Collaboration::pair(&job.client, freelancer.key)[1].as_ref(),
+        ],
+        bump,
+    )]
+    pub collaboration: Account<'info, Collaboration>,
+    
     #[account(mut)]
     pub freelancer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
-pub struct RequestRevision<'info> {
+pub struct DeclineOffer<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
@@ -174,12 +421,35 @@ pub struct RequestRevision<'info> {
     )]
     pub job: Account<'info, Job>,
     
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    /// Client wallet receiving the refund
+    /// CHECK: checked against job.client, only receives funds
     #[account(mut)]
-    pub client: Signer<'info>,
+    pub client: AccountInfo<'info>,
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+    
+    pub freelancer: Signer<'info>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
-pub struct AcceptWork<'info> {
+pub struct ExpireOffer<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
@@ -187,28 +457,31 @@ pub struct AcceptWork<'info> {
     )]
     pub job: Account<'info, Job>,
     
+    /// CHECK: Escrow PDA holding job funds
     #[account(
         mut,
-        seeds = [b"user", client.key().as_ref()],
-        bump,
-        constraint = client_user.authority == client.key()
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
     )]
-    pub client_user: Account<'info, User>,
-    
+    pub escrow: AccountInfo<'info>,
     #[account(
         mut,
-        seeds = [b"user", job.freelancer.unwrap().as_ref()],
-        bump,
-        constraint = freelancer_user.authority == job.freelancer.unwrap()
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
     )]
-    pub freelancer_user: Account<'info, User>,
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
     
     #[account(mut)]
     pub client: Signer<'info>,
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
-pub struct WithdrawFromEscrow<'info> {
+pub struct SubmitWork<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
@@ -224,64 +497,578 @@ pub struct WithdrawFromEscrow<'info> {
     )]
     pub freelancer_user: Account<'info, User>,
     
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RequestRevision<'info> {
     #[account(
         mut,
-        seeds = [b"user", job.client.as_ref()],
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
         bump,
-        constraint = client_user.authority == job.client
     )]
-    pub client_user: Account<'info, User>,
+    pub job: Account<'info, Job>,
     
-    /// CHECK: Escrow PDA holding job funds
-    #[account(
-        mut,
-        seeds = [b"escrow", job.key().as_ref()],
-        bump
-    )]
-    pub escrow: AccountInfo<'info>,
     #[account(mut)]
-    pub freelancer: Signer<'info>,
+    pub client: Signer<'info>,
     
-    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
 }
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
-pub struct DeleteJob<'info> {
+pub struct AcceptWork<'info> {
     #[account(
         mut,
         seeds = [b"job", job_id.to_le_bytes().as_ref()],
         bump,
-        close = authority
     )]
     pub job: Account<'info, Job>,
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
- #[derive(Accounts)]
- #[instruction(job_id: u64)]
- pub struct RaiseDispute<'info> {
     #[account(
-            mut,
-            seeds = [b"job", job_id.to_le_bytes().as_ref()],
-            bump,
-        )]
-    pub job: Account<'info, Job>,
+        mut,
+        seeds = [b"user", client.key().as_ref()],
+        bump,
+        constraint = client_user.authority == client.key()
+    )]
+    pub client_user: Account<'info, User>,
     
     #[account(
         mut,
-        seeds = [b"user", authority.key().as_ref()],
+        seeds = [b"user", job.freelancer.unwrap().as_ref()],
         bump,
-        constraint = raiser_user.authority == authority.key()
+        constraint = freelancer_user.authority == job.freelancer.unwrap()
     )]
-    pub raiser_user: Account<'info, User>,
+    pub freelancer_user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct SubmitMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    pub freelancer: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RequestMilestoneRevision<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ApproveMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", client.key().as_ref()],
+        bump,
+        constraint = client_user.authority == client.key()
+    )]
+    pub client_user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = freelancer_user.authority == freelancer.key()
+    )]
+    pub freelancer_user: Account<'info, User>,
+    
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    /// Assigned freelancer wallet
+    /// CHECK: checked against job.freelancer, only receives funds
+    #[account(mut)]
+    pub freelancer: AccountInfo<'info>,
+    #[account(mut)]
+    pub freelancer_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform treasury wallet, checked against the config
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+    #[account(mut)]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct WithdrawFromEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = freelancer_user.authority == freelancer.key()
+    )]
+    pub freelancer_user: Account<'info, User>,
+    
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+    #[account(mut)]
+    pub freelancer_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform treasury wallet, checked against the config
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+    #[account(mut)]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ConsentToCancellation<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    pub freelancer: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CancelJob<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", client.key().as_ref()],
+        bump,
+        constraint = client_user.authority == client.key()
+    )]
+    pub client_user: Account<'info, User>,
+    
+    /// Assigned freelancer's user account, required once the job is in progress
+    #[account(mut)]
+    pub freelancer_user: Option<Account<'info, User>>,
+    
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ReclaimAfterDeadline<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", client.key().as_ref()],
+        bump,
+        constraint = client_user.authority == client.key()
+    )]
+    pub client_user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", job.freelancer.unwrap().as_ref()],
+        bump,
+        constraint = freelancer_user.authority == job.freelancer.unwrap()
+    )]
+    pub freelancer_user: Account<'info, User>,
+    
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ClaimAfterReviewPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = freelancer_user.authority == freelancer.key()
+    )]
+    pub freelancer_user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", job.client.as_ref()],
+        bump,
+        constraint = client_user.authority == job.client
+    )]
+    pub client_user: Account<'info, User>,
+    
+    /// CHECK: Escrow PDA holding job funds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+    #[account(mut)]
+    pub freelancer_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform treasury wallet, checked against the config
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+    #[account(mut)]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct SubmitReview<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    // Checked against the job's other party in the handler
+    #[account(
+        mut,
+        seeds = [b"user", reviewee_user.authority.as_ref()],
+        bump,
+    )]
+    pub reviewee_user: Account<'info, User>,
+    
+    pub reviewer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ReplyToReview<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    pub author: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct DeleteJob<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+        close = authority
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+ #[derive(Accounts)]
+ #[instruction(job_id: u64)]
+ pub struct RaiseDispute<'info> {
+    #[account(
+            mut,
+            seeds = [b"job", job_id.to_le_bytes().as_ref()],
+            bump,
+        )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        constraint = raiser_user.authority == authority.key()
+    )]
+    pub raiser_user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Juror stake mint from the config, omitted when jurors stake SOL
+    pub stake_mint: Option<Account<'info, Mint>>,
+    /// CHECK: Dispute vault PDA, holds the filing fee and juror stakes
+    #[account(
+        mut,
+        seeds = [b"dispute_vault", job.key().as_ref()],
+        bump
+    )]
+    pub dispute_vault: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"dispute_vault_token", job.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = dispute_vault,
+    )]
+    pub dispute_vault_token: Option<Account<'info, TokenAccount>>,
+    /// Raiser's stake mint token account, pays the filing fee
+    #[account(mut)]
+    pub raiser_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+    
+ }
+ #[derive(Accounts)]
+ #[instruction(job_id: u64, index: u8)]
+ pub struct SubmitEvidence<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + Evidence::LEN,
+        seeds = [b"evidence", job.key().as_ref(), &[index]],
+        bump,
+    )]
+    pub evidence: Account<'info, Evidence>,
     
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub submitter: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
     
     pub system_program: Program<'info, System>,
+ }
+ #[derive(Accounts)]
+ #[instruction(job_id: u64)]
+ pub struct SelectJurors<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        seeds = [b"juror_registry"],
+        bump = juror_registry.bump,
+    )]
+    pub juror_registry: Account<'info, JurorRegistry>,
     
+    /// CHECK: SlotHashes sysvar, read raw for the selection slot's hash
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
  }
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RecuseJuror<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", juror.key().as_ref()],
+        bump,
+        constraint = juror_user.authority == juror.key()
+    )]
+    pub juror_user: Account<'info, User>,
+    
+    /// CHECK: Collaboration PDA of the juror and the raiser
+    pub raiser_collaboration: UncheckedAccount<'info>,
+    
+    /// CHECK: Collaboration PDA of the juror and the against party
+    pub against_collaboration: UncheckedAccount<'info>,
+    
+    pub juror: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
  #[derive(Accounts)]
  #[instruction(job_id: u64)]
  pub struct VoteDispute<'info> {
@@ -292,12 +1079,277 @@ pub struct DeleteJob<'info> {
      )]
      pub job: Account<'info, Job>,
  
-     /// The voter's user account PDA (must correspond to voter)
-     #[account(mut)]
+     #[account(
+         mut,
+         seeds = [b"user", voter.key().as_ref()],
+         bump,
+         constraint = voter_user.authority == voter.key()
+     )]
      pub voter_user: Account<'info, User>,
  
+     /// CHECK: Collaboration PDA of the voter and the raiser, must not exist
+     pub raiser_collaboration: UncheckedAccount<'info>,
+ 
+     /// CHECK: Collaboration PDA of the voter and the against party, must not exist
+     pub against_collaboration: UncheckedAccount<'info>,
+ 
+     #[account(
+         init,
+         payer = voter,
+         space = 8 + VoteRecord::LEN,
+         seeds = [b"vote", job.key().as_ref(), voter.key().as_ref()],
+         bump,
+     )]
+     pub vote_record: Account<'info, VoteRecord>,
+ 
+     /// CHECK: Dispute vault PDA holding juror stakes
+     #[account(
+         mut,
+         seeds = [b"dispute_vault", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault: AccountInfo<'info>,
+     #[account(
+         mut,
+         seeds = [b"dispute_vault_token", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault_token: Option<Account<'info, TokenAccount>>,
+ 
+     #[account(mut)]
+     pub voter: Signer<'info>,
+     #[account(mut)]
+     pub voter_token: Option<Account<'info, TokenAccount>>,
+     
+     #[account(
+         seeds = [b"config"],
+         bump = config.bump,
+     )]
+     pub config: Account<'info, Config>,
+ 
+     pub token_program: Option<Program<'info, Token>>,
+     pub system_program: Program<'info, System>,
+ }
+ #[derive(Accounts)]
+ #[instruction(job_id: u64)]
+ pub struct RevealVote<'info> {
+     #[account(
+         mut,
+         seeds = [b"job", job_id.to_le_bytes().as_ref()],
+         bump,
+     )]
+     pub job: Account<'info, Job>,
+ 
+     #[account(
+         mut,
+         seeds = [b"vote", job.key().as_ref(), voter.key().as_ref()],
+         bump = vote_record.bump,
+     )]
+     pub vote_record: Account<'info, VoteRecord>,
+ 
+     pub voter: Signer<'info>,
+ 
+     #[account(
+         seeds = [b"config"],
+         bump = config.bump,
+     )]
+     pub config: Account<'info, Config>,
+ }
+ #[derive(Accounts)]
+ #[instruction(job_id: u64)]
+ pub struct ClaimJurorStake<'info> {
+     #[account(
+         mut,
+         seeds = [b"job", job_id.to_le_bytes().as_ref()],
+         bump,
+     )]
+     pub job: Account<'info, Job>,
+ 
+     #[account(
+         mut,
+         close = voter,
+         seeds = [b"vote", job.key().as_ref(), voter.key().as_ref()],
+         bump = vote_record.bump,
+     )]
+     pub vote_record: Account<'info, VoteRecord>,
+ 
+     /// CHECK: Dispute vault PDA holding juror stakes
+     #[account(
+         mut,
+         seeds = [b"dispute_vault", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault: AccountInfo<'info>,
+     #[account(
+         mut,
+         seeds = [b"dispute_vault_token", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault_token: Option<Account<'info, TokenAccount>>,
+ 
      #[account(mut)]
      pub voter: Signer<'info>,
+     #[account(mut)]
+     pub voter_token: Option<Account<'info, TokenAccount>>,
+ 
+     pub token_program: Option<Program<'info, Token>>,
+     pub system_program: Program<'info, System>,
+ }
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct SweepJurorStakes<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    /// CHECK: Dispute vault PDA holding juror stakes
+    #[account(
+        mut,
+        seeds = [b"dispute_vault", job.key().as_ref()],
+        bump
+    )]
+    pub dispute_vault: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"dispute_vault_token", job.key().as_ref()],
+        bump
+    )]
+    pub dispute_vault_token: Option<Account<'info, TokenAccount>>,
+    
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform treasury wallet, checked against the config
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+    #[account(mut)]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64, index: u8)]
+pub struct CloseEvidence<'info> {
+    /// CHECK: Closed job PDA, only its address is used
+    #[account(
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = job.data_is_empty() @ ErrorCode::JobStillExists,
+    )]
+    pub job: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        close = submitter,
+        seeds = [b"evidence", job.key().as_ref(), &[index]],
+        bump = evidence.bump,
+        has_one = submitter @ ErrorCode::UnauthorizedUser,
+    )]
+    pub evidence: Account<'info, Evidence>,
+    
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CloseBid<'info> {
+    /// CHECK: Closed job PDA, only its address is used
+    #[account(
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = job.data_is_empty() @ ErrorCode::JobStillExists,
+    )]
+    pub job: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        close = freelancer,
+        seeds = [b"bid", job.key().as_ref(), freelancer.key().as_ref()],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = user.authority == freelancer.key()
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+}
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CloseVoteRecord<'info> {
+    /// CHECK: Closed job PDA, only its address is used
+    #[account(
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = job.data_is_empty() @ ErrorCode::JobStillExists,
+    )]
+    pub job: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        close = voter,
+        seeds = [b"vote", job.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+ #[derive(Accounts)]
+ #[instruction(job_id: u64)]
+ pub struct AppealDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"job", job_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub job: Account<'info, Job>,
+    
+    /// CHECK: Escrow PDA, validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_token", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub appellant: Signer<'info>,
+    #[account(mut)]
+    pub appellant_token: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
  }
  #[derive(Accounts)]
  pub struct FinalizeDispute<'info> {
@@ -311,6 +1363,12 @@ pub struct DeleteJob<'info> {
          bump
      )]
      pub escrow: AccountInfo<'info>,
+     #[account(
+         mut,
+         seeds = [b"escrow_token", job.key().as_ref()],
+         bump
+     )]
+     pub escrow_token: Option<Account<'info, TokenAccount>>,
  
      /// Raiser (creator) wallet
      /// CHECK: only need lamport transfer dest
@@ -323,10 +1381,63 @@ pub struct DeleteJob<'info> {
      pub against: AccountInfo<'info>,
  
      #[account(mut)]
-     pub raiser_user: Account<'info, User>,
+     pub raiser_token: Option<Account<'info, TokenAccount>>,
  
      #[account(mut)]
+     pub against_token: Option<Account<'info, TokenAccount>>,
+ 
+     #[account(
+         mut,
+         seeds = [b"user", raiser.key().as_ref()],
+         bump,
+     )]
+     pub raiser_user: Account<'info, User>,
+ 
+     #[account(
+         mut,
+         seeds = [b"user", against.key().as_ref()],
+         bump,
+     )]
      pub against_user: Account<'info, User>,
+
+     #[account(
+         mut,
+         seeds = [b"juror_registry"],
+         bump = juror_registry.bump,
+     )]
+     pub juror_registry: Account<'info, JurorRegistry>,
+
+     /// CHECK: Dispute vault PDA, validated by seeds
+     #[account(
+         mut,
+         seeds = [b"dispute_vault", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault: AccountInfo<'info>,
+     #[account(
+         mut,
+         seeds = [b"dispute_vault_token", job.key().as_ref()],
+         bump
+     )]
+     pub dispute_vault_token: Option<Account<'info, TokenAccount>>,
+     /// Raiser's stake mint token account, receives a refunded filing fee
+     #[account(mut)]
+     pub raiser_stake_token: Option<Account<'info, TokenAccount>>,
  
+     #[account(
+         seeds = [b"config"],
+         bump = config.bump,
+     )]
+     pub config: Account<'info, Config>,
+     /// CHECK: Platform treasury wallet, checked against the config
+     #[account(
+         mut,
+         constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury
+     )]
+     pub treasury: AccountInfo<'info>,
+     #[account(mut)]
+     pub treasury_token: Option<Account<'info, TokenAccount>>,
+     
+     pub token_program: Option<Program<'info, Token>>,
      pub system_program: Program<'info, System>,
  }
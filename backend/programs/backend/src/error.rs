@@ -98,4 +98,152 @@ pub enum ErrorCode {
     CannotVoteOwnDispute,
     #[msg("Maximum number of voters reached")]
     MaxVotersReached,
+    #[msg("Milestone amount must be greater than zero")]
+    InvalidMilestoneAmount,
+    #[msg("Job is split into milestones, use the milestone instructions")]
+    JobHasMilestones,
+    #[msg("Job has no milestones")]
+    NoMilestones,
+    #[msg("Invalid milestone index")]
+    InvalidMilestoneIndex,
+    #[msg("Earlier milestones must be approved first")]
+    MilestoneOutOfOrder,
+    #[msg("Milestone has already been submitted or approved")]
+    MilestoneNotOpen,
+    #[msg("Milestone has not been submitted")]
+    MilestoneNotSubmitted,
+    #[msg("Invalid freelancer account provided")]
+    InvalidFreelancerAccount,
+    #[msg("Token accounts are required for jobs paid in SPL tokens")]
+    MissingTokenAccount,
+    #[msg("Token account owner or mint does not match the job")]
+    InvalidTokenAccount,
+    #[msg("Job cannot be cancelled in its current state")]
+    JobNotCancellable,
+    #[msg("Work has been submitted, cancellation needs the freelancer's consent")]
+    CancellationConsentRequired,
+    #[msg("Offer duration is invalid")]
+    InvalidOfferDuration,
+    #[msg("No pending offer for this job")]
+    NoPendingOffer,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Offer has not expired yet")]
+    OfferNotExpired,
+    #[msg("Your bid was selected for this job and can no longer be withdrawn")]
+    BidSelected,
+    #[msg("Too many screening questions (max 3)")]
+    TooManyScreeningQuestions,
+    #[msg("Screening question too long (max 100 characters)")]
+    ScreeningQuestionTooLong,
+    #[msg("Cover letter too long (max 1000 characters)")]
+    CoverLetterTooLong,
+    #[msg("Estimated delivery must be in the future and before the job deadline")]
+    InvalidDeliveryDate,
+    #[msg("Every screening question needs exactly one answer")]
+    ScreeningAnswersMismatch,
+    #[msg("Screening answer too long (max 300 characters)")]
+    ScreeningAnswerTooLong,
+    #[msg("Deadline and grace period have not passed yet")]
+    DeadlineNotPassed,
+    #[msg("Work has been submitted and is awaiting review")]
+    WorkAlreadySubmitted,
+    #[msg("Review period has not ended yet")]
+    ReviewPeriodActive,
+    #[msg("Only the platform admin can do this")]
+    NotAdmin,
+    #[msg("Invalid platform config value")]
+    InvalidConfig,
+    #[msg("Treasury account does not match the platform config")]
+    InvalidTreasury,
+    #[msg("This part of the program is paused by the platform admin")]
+    ProgramPaused,
+    #[msg("Review text too long (max 300 characters, 200 for replies)")]
+    ReviewTooLong,
+    #[msg("You have already reviewed this job")]
+    AlreadyReviewed,
+    #[msg("Reviewed user account does not match the job")]
+    InvalidRevieweeAccount,
+    #[msg("Review not found")]
+    ReviewNotFound,
+    #[msg("Only the reviewed party can reply to a review")]
+    NotReviewedParty,
+    #[msg("Review already has a reply")]
+    AlreadyReplied,
+    #[msg("Juror account is too new to vote")]
+    JurorAccountTooNew,
+    #[msg("Juror has not completed enough jobs to vote")]
+    JurorTooInexperienced,
+    #[msg("Juror reputation is too low to vote")]
+    JurorReputationTooLow,
+    #[msg("Jurors cannot vote on disputes of users they have worked with")]
+    JurorWorkedWithParty,
+    #[msg("Collaboration account does not match the voter and party")]
+    InvalidCollaborationAccount,
+    #[msg("Stake mint does not match the platform config")]
+    InvalidStakeMint,
+    #[msg("Dispute has not been resolved yet")]
+    DisputeNotResolved,
+    #[msg("Jurors have not claimed their dispute stakes yet")]
+    StakesUnclaimed,
+    #[msg("Commit phase has ended")]
+    CommitPhaseOver,
+    #[msg("Votes can only be revealed after the commit phase and before voting ends")]
+    RevealPhaseNotActive,
+    #[msg("Vote and salt do not match the commitment")]
+    InvalidReveal,
+    #[msg("Vote has already been revealed")]
+    AlreadyRevealed,
+    #[msg("Ruling bracket must be between 0 and 10")]
+    InvalidRulingBracket,
+    #[msg("Evidence can only be submitted until the commit phase ends")]
+    EvidencePeriodOver,
+    #[msg("You have submitted the maximum amount of evidence for this dispute")]
+    MaxEvidenceReached,
+    #[msg("Evidence index must match the next free slot")]
+    InvalidEvidenceIndex,
+    #[msg("Evidence URI too long (max 200 characters)")]
+    EvidenceUriTooLong,
+    #[msg("Evidence statement too long (max 300 characters)")]
+    EvidenceStatementTooLong,
+    #[msg("Appeals are only accepted during the appeal window after voting ends")]
+    AppealWindowClosed,
+    #[msg("Dispute cannot be finalized while it can still be appealed")]
+    AppealWindowOpen,
+    #[msg("Dispute has already been appealed")]
+    AlreadyAppealed,
+    #[msg("Only the side that lost the ruling can appeal")]
+    NotLosingSide,
+    #[msg("Voting is extended until the quorum is reached")]
+    QuorumNotReached,
+    #[msg("Juror registry is full")]
+    JurorRegistryFull,
+    #[msg("Already registered as a juror")]
+    AlreadyRegisteredJuror,
+    #[msg("Not registered as a juror")]
+    NotRegisteredJuror,
+    #[msg("Only jurors drawn for the panel can vote")]
+    NotOnJuryPanel,
+    #[msg("Jury panel has already been selected")]
+    PanelAlreadySelected,
+    #[msg("Jury panel has not been selected yet")]
+    PanelNotSelected,
+    #[msg("Jury panel cannot be drawn before its selection slot")]
+    SelectionTooEarly,
+    #[msg("Revisions can no longer be requested after the deadline grace period")]
+    RevisionWindowClosed,
+    #[msg("The freelancer still has time to deliver the requested revision")]
+    RevisionWindowOpen,
+    #[msg("Only the program upgrade authority can initialize the config")]
+    NotUpgradeAuthority,
+    #[msg("Jurors can still claim their stakes")]
+    StakeClaimPeriodOpen,
+    #[msg("Only possible once the job has been deleted")]
+    JobStillExists,
+    #[msg("Juror is suspended for missing a vote")]
+    JurorSuspended,
+    #[msg("Only jurors who cannot vote on this dispute can recuse themselves")]
+    JurorEligible,
+    #[msg("The last milestone of a SOL job must cover the escrow's rent-exempt minimum")]
+    MilestoneBelowRent,
 }
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::error::ErrorCode;
+
+/// A program owned balance, such as a job's escrow. The `[seed, job]` PDA
+/// holds lamports when `mint` is `None`, and otherwise is the authority of
+/// the token account holding the balance (`[b"escrow_token", job]` for the
+/// job escrow).
+pub struct Escrow<'a, 'info> {
+    pub seed: &'static [u8],
+    pub job: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub escrow: &'a AccountInfo<'info>,
+    pub bump: u8,
+    pub escrow_token: Option<&'a Account<'info, TokenAccount>>,
+    pub token_program: Option<&'a Program<'info, Token>>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+impl<'a, 'info> Escrow<'a, 'info> {
+    /// The job's payment escrow, the `[b"escrow", job]` PDA.
+    pub fn job(
+        job: Pubkey,
+        mint: Option<Pubkey>,
+        escrow: &'a AccountInfo<'info>,
+        bump: u8,
+        escrow_token: Option<&'a Account<'info, TokenAccount>>,
+        token_program: Option<&'a Program<'info, Token>>,
+        system_program: &'a Program<'info, System>,
+    ) -> Self {
+        Escrow {
+            seed: b"escrow",
+            job,
+            mint,
+            escrow,
+            bump,
+            escrow_token,
+            token_program,
+            system_program,
+        }
+    }
+
+    /// The vault of a job's dispute holding the filing fee and juror
+    /// stakes, the `[b"dispute_vault", job]` PDA.
+    pub fn dispute_vault(
+        job: Pubkey,
+        mint: Option<Pubkey>,
+        vault: &'a AccountInfo<'info>,
+        bump: u8,
+        vault_token: Option<&'a Account<'info, TokenAccount>>,
+        token_program: Option<&'a Program<'info, Token>>,
+        system_program: &'a Program<'info, System>,
+    ) -> Self {
+        Escrow {
+            seed: b"dispute_vault",
+            job,
+            mint,
+            escrow: vault,
+            bump,
+            escrow_token: vault_token,
+            token_program,
+            system_program,
+        }
+    }
+
+    /// Amount currently held, in lamports or token base units.
+    pub fn balance(&self) -> Result<u64> {
+        match self.mint {
+            None => Ok(self.escrow.lamports()),
+            Some(_) => Ok(self.escrow_token()?.amount),
+        }
+    }
+
+    /// Moves `amount` from the payer's wallet (or token account) into escrow.
+    pub fn deposit(
+        &self,
+        payer: &AccountInfo<'info>,
+        payer_token: Option<&Account<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        match self.mint {
+            None => {
+                require!(payer.lamports() >= amount, ErrorCode::InsufficientBalance);
+                let transfer_ix =
+                    system_instruction::transfer(payer.key, self.escrow.key, amount);
+                invoke(
+                    &transfer_ix,
+                    &[
+                        payer.clone(),
+                        self.escrow.clone(),
+                        self.system_program.to_account_info(),
+                    ],
+                )?;
+            }
+            Some(mint) => {
+                let from = check_token_account(payer_token, payer.key, &mint)?;
+                require!(from.amount >= amount, ErrorCode::InsufficientBalance);
+                token::transfer(
+                    CpiContext::new(
+                        self.token_program()?.to_account_info(),
+                        Transfer {
+                            from: from.to_account_info(),
+                            to: self.escrow_token()?.to_account_info(),
+                            authority: payer.clone(),
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pays `amount` out of escrow to the recipient's wallet (or token account).
+    pub fn release(
+        &self,
+        recipient: &AccountInfo<'info>,
+        recipient_token: Option<&Account<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let job_key = self.job;
+        let seeds = &[self.seed, job_key.as_ref(), &[self.bump]];
+        let signer_seeds = &[&seeds[..]];
+        match self.mint {
+            None => {
+                let transfer_ix =
+                    system_instruction::transfer(self.escrow.key, recipient.key, amount);
+                invoke_signed(
+                    &transfer_ix,
+                    &[
+                        self.escrow.clone(),
+                        recipient.clone(),
+                        self.system_program.to_account_info(),
+                    ],
+                    signer_seeds,
+                )?;
+            }
+            Some(mint) => {
+                let to = check_token_account(recipient_token, recipient.key, &mint)?;
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        self.token_program()?.to_account_info(),
+                        Transfer {
+                            from: self.escrow_token()?.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: self.escrow.clone(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pays `amount` out of escrow, sending `fee` of it to the platform
+    /// treasury and the rest to the recipient.
+    pub fn release_with_fee(
+        &self,
+        recipient: &AccountInfo<'info>,
+        recipient_token: Option<&Account<'info, TokenAccount>>,
+        treasury: &AccountInfo<'info>,
+        treasury_token: Option<&Account<'info, TokenAccount>>,
+        amount: u64,
+        fee: u64,
+    ) -> Result<()> {
+        let net = amount
+            .checked_sub(fee)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        self.release(treasury, treasury_token, fee)?;
+        self.release(recipient, recipient_token, net)
+    }
+
+    fn escrow_token(&self) -> Result<&'a Account<'info, TokenAccount>> {
+        self.escrow_token
+            .ok_or_else(|| error!(ErrorCode::MissingTokenAccount))
+    }
+
+    fn token_program(&self) -> Result<&'a Program<'info, Token>> {
+        self.token_program
+            .ok_or_else(|| error!(ErrorCode::MissingTokenAccount))
+    }
+}
+
+/// Ensures a user token account was supplied and belongs to `owner` for `mint`.
+fn check_token_account<'a, 'info>(
+    account: Option<&'a Account<'info, TokenAccount>>,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<&'a Account<'info, TokenAccount>> {
+    let account = account.ok_or(ErrorCode::MissingTokenAccount)?;
+    require!(
+        account.owner == *owner && account.mint == *mint,
+        ErrorCode::InvalidTokenAccount
+    );
+    Ok(account)
+}
@@ -0,0 +1,354 @@
+use anchor_lang::prelude::*;
+
+// Amounts are in lamports for SOL jobs and in token base units for SPL jobs.
+// `milestone_index` is `None` for jobs paid as a single lump sum.
+
+#[event]
+pub struct UserRegistered {
+    pub user: Pubkey,
+    pub is_client: bool,
+    pub is_freelancer: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobCreated {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub budget: u64,
+    pub deadline: i64,
+    pub mint: Option<Pubkey>,
+    pub milestone_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobUpdated {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub budget: u64,
+    pub deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalSubmitted {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub proposed_amount: u64,
+    pub estimated_delivery: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalUpdated {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub proposed_amount: u64,
+    pub estimated_delivery: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalWithdrawn {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobOffered {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferDeclined {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferExpired {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobAssigned {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WorkSubmitted {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub milestone_index: Option<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevisionRequested {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub milestone_index: Option<u8>,
+    pub timestamp: i64,
+}
+
+/// Emitted when the client accepts work, or when it is approved
+/// automatically after the review period (`auto_approved`).
+#[event]
+pub struct WorkAccepted {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub milestone_index: Option<u8>,
+    pub amount: u64,
+    pub auto_approved: bool,
+    pub timestamp: i64,
+}
+
+/// Funds paid out of escrow. `fee` is the part sent to the platform treasury.
+#[event]
+pub struct EscrowReleased {
+    pub job_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowRefunded {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CancellationConsented {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// `missed_deadline` is set when the client reclaimed escrow after the
+/// freelancer failed to deliver.
+#[event]
+pub struct JobCancelled {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Option<Pubkey>,
+    pub refunded: u64,
+    pub missed_deadline: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeRaised {
+    pub job_id: u64,
+    pub raiser: Pubkey,
+    pub against: Pubkey,
+    pub filing_fee: u64,
+    pub commit_end: i64,
+    pub voting_end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EvidenceSubmitted {
+    pub job_id: u64,
+    pub submitter: Pubkey,
+    pub evidence: Pubkey,
+    pub index: u8,
+    pub content_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JurorRegistered {
+    pub juror: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JurorUnregistered {
+    pub juror: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Jurors drawn onto a dispute panel, either a new panel or replacements
+/// for absent jurors when voting is extended.
+#[event]
+pub struct JurorsSelected {
+    pub job_id: u64,
+    pub round: u8,
+    pub jurors: Vec<Pubkey>,
+    pub commit_end: i64,
+    pub voting_end: i64,
+    pub timestamp: i64,
+}
+
+/// The selection slot's hash left the SlotHashes window before the panel
+/// was drawn, the draw now uses `selection_slot`.
+#[event]
+pub struct JurySelectionRescheduled {
+    pub job_id: u64,
+    pub round: u8,
+    pub selection_slot: u64,
+    pub timestamp: i64,
+}
+
+/// A drawn juror who could not vote gave up their seat.
+#[event]
+pub struct JurorRecused {
+    pub job_id: u64,
+    pub round: u8,
+    pub juror: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Panel members who did not vote, suspended in the juror registry.
+#[event]
+pub struct AbsentJurorsRemoved {
+    pub job_id: u64,
+    pub round: u8,
+    pub jurors: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// A juror committed a secret ballot, the vote is only known once revealed.
+#[event]
+pub struct VoteCast {
+    pub job_id: u64,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+}
+
+/// `bracket` is the raiser's share of escrow in steps of 10%.
+#[event]
+pub struct VoteRevealed {
+    pub job_id: u64,
+    pub voter: Pubkey,
+    pub bracket: u8,
+    pub timestamp: i64,
+}
+
+/// A round missed its quorum of commits. Empty seats are drawn from
+/// `selection_slot` and commits reopen once the panel is selected.
+#[event]
+pub struct VotingExtended {
+    pub job_id: u64,
+    pub round: u8,
+    pub extensions: u8,
+    pub commit_count: u64,
+    pub selection_slot: u64,
+    pub timestamp: i64,
+}
+
+/// The first ruling was appealed and a second jury round opened.
+/// `raiser_share` is the appealed ruling, `None` if it reached none.
+#[event]
+pub struct DisputeAppealed {
+    pub job_id: u64,
+    pub appellant: Pubkey,
+    pub bond: u64,
+    pub raiser_share: Option<u8>,
+    pub commit_end: i64,
+    pub voting_end: i64,
+    pub timestamp: i64,
+}
+
+/// `round` is the round whose ruling stands, `votes_revealed` is from the
+/// last round held.
+#[event]
+pub struct DisputeFinalized {
+    pub job_id: u64,
+    pub raiser: Pubkey,
+    pub against: Pubkey,
+    pub round: u8,
+    pub raiser_share: u8, // percent
+    pub votes_revealed: u64,
+    pub raiser_amount: u64,
+    pub against_amount: u64,
+    pub filing_fee_refunded: bool,
+    pub timestamp: i64,
+}
+
+/// The appeal bond goes back to the appellant if the appeal improved their
+/// share, and to the other party otherwise, including when it reached no ruling.
+#[event]
+pub struct AppealBondReleased {
+    pub job_id: u64,
+    pub appellant: Pubkey,
+    pub recipient: Pubkey,
+    pub bond: u64,
+    pub refunded: bool,
+    pub timestamp: i64,
+}
+
+/// `payout` is 0 for slashed jurors, and includes the juror's part of
+/// the filing fee when the raiser lost.
+#[event]
+pub struct JurorStakeClaimed {
+    pub job_id: u64,
+    pub voter: Pubkey,
+    pub stake: u64,
+    pub payout: u64,
+    pub timestamp: i64,
+}
+
+/// Juror payouts left unclaimed after the stake claim period, sent to the treasury.
+#[event]
+pub struct JurorStakesSwept {
+    pub job_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReviewSubmitted {
+    pub job_id: u64,
+    pub reviewer: Pubkey,
+    pub reviewee: Pubkey,
+    pub rating: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReviewReplied {
+    pub job_id: u64,
+    pub reviewer: Pubkey,
+    pub reviewee: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobDeleted {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub platform_fee_bps: u16,
+    pub paused: u8,
+    pub timestamp: i64,
+}
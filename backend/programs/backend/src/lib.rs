@@ -1,13 +1,17 @@
 use anchor_lang::prelude::*;
 pub mod contexts;
 pub mod error;
+pub mod escrow;
+pub mod events;
 pub mod state;
 
 use contexts::*;
 use error::ErrorCode;
+use escrow::Escrow;
+use events::*;
+use sha2::{Digest, Sha256};
 use state::*;
 
-use anchor_lang::solana_program::system_instruction;
 declare_id!("TCmSPaJcRMbtzJbkGcGrJtcsjzNRpAwFRNxhqTC9BZZ");
 
 #[program]
@@ -26,7 +30,7 @@ pub mod backend {
         user.name = name;
         user.is_client = is_client;
         user.is_freelancer = is_freelancer;
-        user.reputation = 50;
+        user.reputation = REPUTATION_BASELINE;
         user.completed_jobs = 0;
         user.created_at = Clock::get()?.unix_timestamp;
         user.resume = resume;
@@ -37,11 +41,27 @@ pub mod backend {
         user.active_jobs = 0;
         user.pending_jobs = 0;
         user.cancelled_jobs = 0;
+        user.freelancer_ratings = [0; 5];
+        user.client_ratings = [0; 5];
+        user.average_rating = 0;
+        user.on_time_deliveries = 0;
+        user.late_deliveries = 0;
+        user.dispute_points_won = 0;
+        user.dispute_points_lost = 0;
+        user.freelancer_score = REPUTATION_BASELINE;
+        user.client_score = REPUTATION_BASELINE;
+        user.last_activity_at = user.created_at;
 
         require!(
             user.authority == ctx.accounts.authority.key(),
             ErrorCode::AuthorityMismatch
         );
+        emit!(UserRegistered {
+            user: user.authority,
+            is_client,
+            is_freelancer,
+            timestamp: user.created_at,
+        });
         Ok(())
     }
 
@@ -105,6 +125,7 @@ pub mod backend {
         Ok(())
     }
     
+    #[allow(clippy::too_many_arguments)]
     pub fn create_job(
         ctx: Context<CreateJob>,
         title: String,
@@ -113,18 +134,45 @@ pub mod backend {
         deadline: i64,
         skills: Vec<String>,
         category: String,
+        milestones: Vec<MilestoneInput>,
+        screening_questions: Vec<String>,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_JOBS)?;
         let job = &mut ctx.accounts.job;
         let job_counter = &mut ctx.accounts.job_counter;
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
         let user = &mut ctx.accounts.user;
         // Validations
-        require!(deadline > now, ErrorCode::InvalidDeadline);
-        require!(title.len() <= 100, ErrorCode::TitleTooLong);
-        require!(description.len() <= 1000, ErrorCode::DescriptionTooLong);
-        require!(skills.len() <= 10, ErrorCode::TooManySkills);
-        require!(category.len() <= 50, ErrorCode::CategoryTooLong);
+        validate_job_fields(
+            Some(&title),
+            Some(&description),
+            Some(deadline),
+            Some(&skills),
+            Some(&category),
+            now,
+        )?;
+        require!(
+            screening_questions.len() <= MAX_SCREENING_QUESTIONS,
+            ErrorCode::TooManyScreeningQuestions
+        );
+        require!(
+            screening_questions.iter().all(|question| question.len() <= 100),
+            ErrorCode::ScreeningQuestionTooLong
+        );
+        // SPL jobs get their escrow token account created up front
+        let mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+        require!(
+            mint.is_none() || ctx.accounts.escrow_token.is_some(),
+            ErrorCode::MissingTokenAccount
+        );
+        let milestones = build_milestones(
+            milestones,
+            budget,
+            deadline,
+            now,
+            milestone_tail_minimum(mint)?,
+        )?;
         
         // Get the universal job ID from counter
         let global_job_id = job_counter.count;
@@ -144,7 +192,7 @@ pub mod backend {
         job.status = JobStatus::Open;
         job.created_at = now;
         job.updated_at = now;
-        job.bidders = Vec::new();
+        job.bid_count = 0;
         job.reviews = Vec::new();
         job.dispute = None;
         job.skills = skills;
@@ -158,12 +206,108 @@ pub mod backend {
         job.work_approved = false;
         job.work_approved_at = None;
         job.revision_request = None;
+        job.milestones = milestones;
+        job.mint = mint;
+        job.cancellation_consent = false;
+        job.offered_amount = 0;
+        job.offer_expires_at = None;
+        job.screening_questions = screening_questions;
+        job.terms_updated_at = None;
+        job.revision_requested_at = None;
         user.active_jobs += 1;
-        msg!(
-            "Job created successfully: {} (Universal ID: {})", 
-            job.title, 
-            global_job_id
+        emit!(JobCreated {
+            job_id: global_job_id,
+            client: job.client,
+            budget,
+            deadline,
+            mint,
+            milestone_count: job.milestones.len() as u8,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_job(
+        ctx: Context<UpdateJob>,
+        _job_id: u64,
+        new_title: Option<String>,
+        new_description: Option<String>,
+        new_budget: Option<u64>,
+        new_deadline: Option<i64>,
+        new_skills: Option<Vec<String>>,
+        new_category: Option<String>,
+        new_milestones: Option<Vec<MilestoneInput>>,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_JOBS)?;
+        let job = &mut ctx.accounts.job;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
         );
+        require!(job.status == JobStatus::Open, ErrorCode::JobNotOpen);
+        
+        // Same validations as create_job
+        validate_job_fields(
+            new_title.as_deref(),
+            new_description.as_deref(),
+            new_deadline,
+            new_skills.as_deref(),
+            new_category.as_deref(),
+            now,
+        )?;
+        
+        // Milestones have to keep matching the budget and deadline
+        if new_budget.is_some() || new_deadline.is_some() || new_milestones.is_some() {
+            let milestones = new_milestones.unwrap_or_else(|| {
+                job.milestones
+                    .iter()
+                    .map(|milestone| MilestoneInput {
+                        title: milestone.title.clone(),
+                        amount: milestone.amount,
+                        due_date: milestone.due_date,
+                    })
+                    .collect()
+            });
+            job.milestones = build_milestones(
+                milestones,
+                new_budget.unwrap_or(job.budget),
+                new_deadline.unwrap_or(job.deadline),
+                now,
+                milestone_tail_minimum(job.mint)?,
+            )?;
+        }
+        
+        if let Some(title) = new_title {
+            job.title = title;
+        }
+        if let Some(description) = new_description {
+            job.description = description;
+        }
+        if let Some(budget) = new_budget {
+            job.budget = budget;
+        }
+        if let Some(deadline) = new_deadline {
+            job.deadline = deadline;
+        }
+        if let Some(skills) = new_skills {
+            job.skills = skills;
+        }
+        if let Some(category) = new_category {
+            job.category = category;
+        }
+        // Lets bidders see that the terms changed after they bid
+        job.terms_updated_at = Some(now);
+        job.updated_at = now;
+        
+        emit!(JobUpdated {
+            job_id: job.job_id,
+            client: job.client,
+            budget: job.budget,
+            deadline: job.deadline,
+            timestamp: now,
+        });
         Ok(())
     }
     pub fn initialize_job_counter(ctx: Context<InitializeJobCounter>) -> Result<()> {
@@ -172,59 +316,325 @@ pub mod backend {
         msg!("Job counter initialized to 0");
         Ok(())
     }
+    pub fn initialize_juror_registry(ctx: Context<InitializeJurorRegistry>) -> Result<()> {
+        let juror_registry = &mut ctx.accounts.juror_registry;
+        juror_registry.jurors = Vec::new();
+        juror_registry.bump = ctx.bumps.juror_registry;
+        msg!("Juror registry initialized");
+        Ok(())
+    }
+    /// Opts in to jury duty, dispute panels are drawn from the registry.
+    /// Whether the juror qualifies for appeal panels is recorded now, and
+    /// refreshed by unregistering and registering again. A suspended juror
+    /// registers again once the suspension has passed.
+    pub fn register_juror(ctx: Context<RegisterJuror>) -> Result<()> {
+        let juror = ctx.accounts.juror.key();
+        let juror_registry = &mut ctx.accounts.juror_registry;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let existing = juror_registry.find(&juror);
+        if let Some(index) = existing {
+            let suspended_until = juror_registry.jurors[index].suspended_until;
+            require!(suspended_until != 0, ErrorCode::AlreadyRegisteredJuror);
+            require!(now >= suspended_until, ErrorCode::JurorSuspended);
+        } else {
+            require!(
+                juror_registry.jurors.len() < MAX_REGISTERED_JURORS,
+                ErrorCode::JurorRegistryFull
+            );
+        }
+        let config = &ctx.accounts.config;
+        let juror_user = &mut ctx.accounts.juror_user;
+        juror_user.touch(now, config.reputation_half_life);
+        config.require_eligible_juror(juror_user, now, 1)?;
+        let entry = RegisteredJuror {
+            juror,
+            appeal_eligible: config.require_eligible_juror(juror_user, now, 2).is_ok(),
+            suspended_until: 0,
+        };
+        match existing {
+            Some(index) => juror_registry.jurors[index] = entry,
+            None => juror_registry.jurors.push(entry),
+        }
+        
+        emit!(JurorRegistered {
+            juror,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    pub fn unregister_juror(ctx: Context<UnregisterJuror>) -> Result<()> {
+        let juror = ctx.accounts.juror.key();
+        let juror_registry = &mut ctx.accounts.juror_registry;
+        
+        let now = Clock::get()?.unix_timestamp;
+        
+        let index = juror_registry.find(&juror).ok_or(ErrorCode::NotRegisteredJuror)?;
+        // Leaving does not cut a suspension short
+        require!(
+            now >= juror_registry.jurors[index].suspended_until,
+            ErrorCode::JurorSuspended
+        );
+        juror_registry.jurors.swap_remove(index);
+        
+        emit!(JurorUnregistered {
+            juror,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        platform_fee_bps: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.treasury = treasury;
+        config.platform_fee_bps = platform_fee_bps;
+        config.max_voters = MAX_VOTERS as u64;
+        config.max_voting_period = DEFAULT_MAX_VOTING_PERIOD;
+        config.max_offer_duration = DEFAULT_MAX_OFFER_DURATION;
+        config.deadline_grace_period = DEFAULT_DEADLINE_GRACE_PERIOD;
+        config.review_period = DEFAULT_REVIEW_PERIOD;
+        config.reputation_half_life = DEFAULT_REPUTATION_HALF_LIFE;
+        config.juror_min_account_age = DEFAULT_JUROR_MIN_ACCOUNT_AGE;
+        config.juror_min_completed_jobs = DEFAULT_JUROR_MIN_COMPLETED_JOBS;
+        config.juror_min_reputation = DEFAULT_JUROR_MIN_REPUTATION;
+        config.stake_mint = None;
+        config.juror_stake = DEFAULT_JUROR_STAKE;
+        config.reveal_period = DEFAULT_REVEAL_PERIOD;
+        config.quorum = DEFAULT_QUORUM;
+        config.max_voting_extensions = DEFAULT_MAX_VOTING_EXTENSIONS;
+        config.fallback_raiser_share = DEFAULT_FALLBACK_RAISER_SHARE;
+        config.appeal_period = DEFAULT_APPEAL_PERIOD;
+        config.appeal_bond_bps = DEFAULT_APPEAL_BOND_BPS;
+        config.appeal_quorum = DEFAULT_APPEAL_QUORUM;
+        config.appeal_juror_min_completed_jobs = DEFAULT_APPEAL_JUROR_MIN_COMPLETED_JOBS;
+        config.appeal_juror_min_reputation = DEFAULT_APPEAL_JUROR_MIN_REPUTATION;
+        config.panel_size = DEFAULT_PANEL_SIZE;
+        config.appeal_panel_size = DEFAULT_APPEAL_PANEL_SIZE;
+        config.dispute_fee = DEFAULT_DISPUTE_FEE;
+        config.stake_claim_period = DEFAULT_STAKE_CLAIM_PERIOD;
+        config.juror_suspension_period = DEFAULT_JUROR_SUSPENSION_PERIOD;
+        config.paused = 0;
+        config.bump = ctx.bumps.config;
+        validate_config(config, Rent::get()?.minimum_balance(0))?;
+        
+        msg!("Platform config initialized, admin {}", config.admin);
+        Ok(())
+    }
+    pub fn update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        
+        if let Some(admin) = update.admin {
+            config.admin = admin;
+        }
+        if let Some(treasury) = update.treasury {
+            config.treasury = treasury;
+        }
+        if let Some(platform_fee_bps) = update.platform_fee_bps {
+            config.platform_fee_bps = platform_fee_bps;
+        }
+        if let Some(max_voters) = update.max_voters {
+            config.max_voters = max_voters;
+        }
+        if let Some(max_voting_period) = update.max_voting_period {
+            config.max_voting_period = max_voting_period;
+        }
+        if let Some(max_offer_duration) = update.max_offer_duration {
+            config.max_offer_duration = max_offer_duration;
+        }
+        if let Some(deadline_grace_period) = update.deadline_grace_period {
+            config.deadline_grace_period = deadline_grace_period;
+        }
+        if let Some(review_period) = update.review_period {
+            config.review_period = review_period;
+        }
+        if let Some(reputation_half_life) = update.reputation_half_life {
+            config.reputation_half_life = reputation_half_life;
+        }
+        if let Some(juror_min_account_age) = update.juror_min_account_age {
+            config.juror_min_account_age = juror_min_account_age;
+        }
+        if let Some(juror_min_completed_jobs) = update.juror_min_completed_jobs {
+            config.juror_min_completed_jobs = juror_min_completed_jobs;
+        }
+        if let Some(juror_min_reputation) = update.juror_min_reputation {
+            config.juror_min_reputation = juror_min_reputation;
+        }
+        if let Some(stake_mint) = update.stake_mint {
+            config.stake_mint = stake_mint;
+        }
+        if let Some(juror_stake) = update.juror_stake {
+            config.juror_stake = juror_stake;
+        }
+        if let Some(reveal_period) = update.reveal_period {
+            config.reveal_period = reveal_period;
+        }
+        if let Some(quorum) = update.quorum {
+            config.quorum = quorum;
+        }
+        if let Some(max_voting_extensions) = update.max_voting_extensions {
+            config.max_voting_extensions = max_voting_extensions;
+        }
+        if let Some(fallback_raiser_share) = update.fallback_raiser_share {
+            config.fallback_raiser_share = fallback_raiser_share;
+        }
+        if let Some(appeal_period) = update.appeal_period {
+            config.appeal_period = appeal_period;
+        }
+        if let Some(appeal_bond_bps) = update.appeal_bond_bps {
+            config.appeal_bond_bps = appeal_bond_bps;
+        }
+        if let Some(appeal_quorum) = update.appeal_quorum {
+            config.appeal_quorum = appeal_quorum;
+        }
+        if let Some(appeal_juror_min_completed_jobs) = update.appeal_juror_min_completed_jobs {
+            config.appeal_juror_min_completed_jobs = appeal_juror_min_completed_jobs;
+        }
+        if let Some(appeal_juror_min_reputation) = update.appeal_juror_min_reputation {
+            config.appeal_juror_min_reputation = appeal_juror_min_reputation;
+        }
+        if let Some(panel_size) = update.panel_size {
+            config.panel_size = panel_size;
+        }
+        if let Some(appeal_panel_size) = update.appeal_panel_size {
+            config.appeal_panel_size = appeal_panel_size;
+        }
+        if let Some(dispute_fee) = update.dispute_fee {
+            config.dispute_fee = dispute_fee;
+        }
+        if let Some(stake_claim_period) = update.stake_claim_period {
+            config.stake_claim_period = stake_claim_period;
+        }
+        if let Some(juror_suspension_period) = update.juror_suspension_period {
+            config.juror_suspension_period = juror_suspension_period;
+        }
+        validate_config(config, Rent::get()?.minimum_balance(0))?;
+        
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            treasury: config.treasury,
+            platform_fee_bps: config.platform_fee_bps,
+            paused: config.paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+    /// Sets the pause bitmask (`PAUSE_*` flags). Refund paths are never
+    /// paused, and neither are the dispute steps with a deadline (evidence,
+    /// commits, reveals and appeals) since their clocks keep running.
+    pub fn set_pause(ctx: Context<UpdateConfig>, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL == 0, ErrorCode::InvalidConfig);
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            treasury: config.treasury,
+            platform_fee_bps: config.platform_fee_bps,
+            paused: config.paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+    /// Offers the job to a bidder and funds escrow. The freelancer still has
+    /// to call `accept_offer` before the job is in progress.
     pub fn assign_job(
         ctx: Context<AssignJob>, 
         _job_id: u64, 
         freelancer: Pubkey, 
-        bid_amount: u64
+        bid_amount: u64,
+        offer_duration: i64,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_FUNDS)?;
         let job = &mut ctx.accounts.job;
         let clock = Clock::get()?;
-        let freelancer_user = &mut ctx.accounts.freelancer_user;
-        let client_user = &mut ctx.accounts.client_user;
         let now = clock.unix_timestamp;
         
-        // Verify bid exists
-        let bid_exists = job
-            .bidders
-            .iter()
-            .any(|bid| bid.freelancer == freelancer && bid.proposed_amount == bid_amount);
-        require!(bid_exists, ErrorCode::BidNotFound);
+        // Verify bid exists at its current amount
+        require!(
+            ctx.accounts.bid.proposed_amount == bid_amount,
+            ErrorCode::BidNotFound
+        );
     
         // Verify job is open
         require!(job.status == JobStatus::Open, ErrorCode::JobNotOpen);
         
         // Verify client is the job owner
         require!(job.client == ctx.accounts.client.key(), ErrorCode::NotJobClient);
-    
-        // Verify client has enough SOL for escrow
-        let client_lamports = ctx.accounts.client.lamports();
         require!(
-            client_lamports >= bid_amount,
-            ErrorCode::InsufficientBalance
+            offer_duration > 0 && offer_duration <= ctx.accounts.config.max_offer_duration,
+            ErrorCode::InvalidOfferDuration
         );
-    
-        // Transfer funds to escrow
-        let transfer_ix = system_instruction::transfer(
-            &ctx.accounts.client.key(),
-            &ctx.accounts.escrow.key(),
+        // The milestones have to stay payable once scaled to the bid
+        rescale_milestones(
+            &mut job.milestones.clone(),
+            job.budget,
             bid_amount,
-        );
+            milestone_tail_minimum(job.mint)?,
+        )?;
     
-        anchor_lang::solana_program::program::invoke(
-            &transfer_ix,
-            &[
-                ctx.accounts.client.to_account_info(),
-                ctx.accounts.escrow.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
+        // Transfer funds (SOL or SPL) to escrow
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        escrow.deposit(
+            &ctx.accounts.client.to_account_info(),
+            ctx.accounts.client_token.as_ref(),
+            bid_amount,
         )?;
         
-        // Update freelancer counters
-        freelancer_user.pending_jobs = freelancer_user
-            .pending_jobs
-            .checked_add(1).unwrap();
-            
+        // Record the pending offer
+        job.freelancer = Some(freelancer);
+        job.status = JobStatus::Offered;
+        job.offered_amount = bid_amount;
+        job.offer_expires_at = Some(now + offer_duration);
+        job.updated_at = now;
+        job.escrow = match &ctx.accounts.escrow_token {
+            Some(escrow_token) => escrow_token.key(),
+            None => ctx.accounts.escrow.key(),
+        };
+    
+        emit!(JobOffered {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer,
+            amount: bid_amount,
+            expires_at: now + offer_duration,
+            timestamp: now,
+        });
+    
+        Ok(())
+    }
+    pub fn accept_offer(
+        ctx: Context<AcceptOffer>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let client_user = &mut ctx.accounts.client_user;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(job.status == JobStatus::Offered, ErrorCode::NoPendingOffer);
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::NotAssignedFreelancer
+        );
+        require!(
+            job.offer_expires_at.is_some_and(|expires_at| now <= expires_at),
+            ErrorCode::OfferExpired
+        );
+        
+        // The winning bid already counts towards the freelancer's pending jobs
+        ctx.accounts.bid.selected = true;
+        
         // Update job counters for client
         client_user.pending_jobs = client_user
             .pending_jobs
@@ -235,28 +645,145 @@ pub mod backend {
             .checked_sub(1)
             .unwrap();
         
-        // Update job status and assign freelancer
-        job.freelancer = Some(freelancer);
+        // Milestone amounts follow the agreed bid
+        let original_budget = job.budget;
+        let agreed_amount = job.offered_amount;
+        let min_tail = milestone_tail_minimum(job.mint)?;
+        rescale_milestones(&mut job.milestones, original_budget, agreed_amount, min_tail)?;
+        
         job.status = JobStatus::InProgress;
+        job.budget = agreed_amount;
+        job.offer_expires_at = None;
         job.updated_at = now;
-        job.budget = bid_amount;
-        job.escrow = ctx.accounts.escrow.key(); 
-    
-        msg!(
-            "Job '{}' assigned to freelancer: {}. Amount {} lamports transferred to escrow",
-            job.title,
-            freelancer,
-            bid_amount
+        
+        // Remember the pair so neither can judge the other's disputes
+        let collaboration = &mut ctx.accounts.collaboration;
+        collaboration.parties = [job.client, ctx.accounts.freelancer.key()];
+        collaboration.parties.sort();
+        collaboration.jobs = collaboration.jobs.checked_add(1).unwrap();
+        collaboration.last_job_at = now;
+        collaboration.bump = ctx.bumps.collaboration;
+        
+        emit!(JobAssigned {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: ctx.accounts.freelancer.key(),
+            amount: agreed_amount,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    pub fn decline_offer(
+        ctx: Context<DeclineOffer>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        
+        require!(job.status == JobStatus::Offered, ErrorCode::NoPendingOffer);
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::NotAssignedFreelancer
         );
-    
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let refunded = escrow.balance()?;
+        escrow.release(
+            &ctx.accounts.client,
+            ctx.accounts.client_token.as_ref(),
+            refunded,
+        )?;
+        let now = Clock::get()?.unix_timestamp;
+        reopen_job(job, now);
+        
+        emit!(OfferDeclined {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: ctx.accounts.freelancer.key(),
+            timestamp: now,
+        });
+        emit!(EscrowRefunded {
+            job_id: job.job_id,
+            client: job.client,
+            amount: refunded,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    pub fn expire_offer(
+        ctx: Context<ExpireOffer>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(job.status == JobStatus::Offered, ErrorCode::NoPendingOffer);
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        require!(
+            job.offer_expires_at.is_some_and(|expires_at| now > expires_at),
+            ErrorCode::OfferNotExpired
+        );
+        
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let refunded = escrow.balance()?;
+        escrow.release(
+            &ctx.accounts.client.to_account_info(),
+            ctx.accounts.client_token.as_ref(),
+            refunded,
+        )?;
+        let freelancer = job.freelancer.unwrap_or_default();
+        reopen_job(job, now);
+        
+        emit!(OfferExpired {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer,
+            timestamp: now,
+        });
+        emit!(EscrowRefunded {
+            job_id: job.job_id,
+            client: job.client,
+            amount: refunded,
+            timestamp: now,
+        });
+        
         Ok(())
     }
     pub fn submit_proposal(
         ctx: Context<SubmitProposal>,
         _job_id: u64,
         proposed_amount: u64,
+        cover_letter: String,
+        estimated_delivery: i64,
+        screening_answers: Vec<String>,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_BIDDING)?;
         let job = &mut ctx.accounts.job;
+        let bid = &mut ctx.accounts.bid;
         let user = &mut ctx.accounts.user;
         let freelancer = ctx.accounts.freelancer.key();
         let clock = Clock::get()?;
@@ -280,26 +807,118 @@ pub mod backend {
             job.freelancer.is_none(),
             ErrorCode::JobAlreadyAssigned
         );
-        require!(
-            !job.bidders.iter().any(|bid| bid.freelancer == freelancer),
-            ErrorCode::AlreadySubmittedBid
-        );
         require!(
             proposed_amount > 0, 
             ErrorCode::InvalidBidAmount
         );
+        validate_proposal_details(
+            job,
+            &cover_letter,
+            estimated_delivery,
+            &screening_answers,
+            clock.unix_timestamp,
+        )?;
+        bid.job = job.key();
+        bid.freelancer = freelancer;
+        bid.proposed_amount = proposed_amount;
+        bid.timestamp = clock.unix_timestamp;
+        bid.cover_letter = cover_letter;
+        bid.estimated_delivery = estimated_delivery;
+        bid.screening_answers = screening_answers;
+        bid.selected = false;
+        job.bid_count = job.bid_count.checked_add(1).unwrap();
+        job.updated_at = clock.unix_timestamp;
+        user.pending_jobs += 1;
+        
+        emit!(ProposalSubmitted {
+            job_id: job.job_id,
+            freelancer,
+            proposed_amount,
+            estimated_delivery: bid.estimated_delivery,
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    pub fn update_proposal(
+        ctx: Context<UpdateProposal>,
+        _job_id: u64,
+        new_amount: u64,
+        new_cover_letter: Option<String>,
+        new_estimated_delivery: Option<i64>,
+        new_screening_answers: Option<Vec<String>>,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_BIDDING)?;
+        let job = &ctx.accounts.job;
+        let bid = &mut ctx.accounts.bid;
+        let freelancer = ctx.accounts.freelancer.key();
+        let clock = Clock::get()?;
+        
+        require!(
+            job.status == JobStatus::Open,
+            ErrorCode::JobNotOpen
+        );
         require!(
-            job.bidders.len() < 50,
-            ErrorCode::MaxBidsReached
+            new_amount > 0,
+            ErrorCode::InvalidBidAmount
         );
-        let bid = Bid {
+        if let Some(cover_letter) = new_cover_letter {
+            bid.cover_letter = cover_letter;
+        }
+        if let Some(estimated_delivery) = new_estimated_delivery {
+            bid.estimated_delivery = estimated_delivery;
+        }
+        if let Some(screening_answers) = new_screening_answers {
+            bid.screening_answers = screening_answers;
+        }
+        validate_proposal_details(
+            job,
+            &bid.cover_letter,
+            bid.estimated_delivery,
+            &bid.screening_answers,
+            clock.unix_timestamp,
+        )?;
+        bid.proposed_amount = new_amount;
+        bid.timestamp = clock.unix_timestamp;
+        
+        emit!(ProposalUpdated {
+            job_id: job.job_id,
             freelancer,
-            proposed_amount,
+            proposed_amount: new_amount,
+            estimated_delivery: bid.estimated_delivery,
             timestamp: clock.unix_timestamp,
-        };
-        job.bidders.push(bid);
+        });
+        
+        Ok(())
+    }
+    pub fn withdraw_proposal(
+        ctx: Context<WithdrawProposal>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let user = &mut ctx.accounts.user;
+        let freelancer = ctx.accounts.freelancer.key();
+        let clock = Clock::get()?;
+        
+        if job.freelancer == Some(freelancer) {
+            // The selected bid can only be closed for rent once the job is over,
+            // its pending job was already settled then
+            require!(
+                job.status == JobStatus::Completed || job.status == JobStatus::Cancelled,
+                ErrorCode::BidSelected
+            );
+        } else {
+            // Losing bidders may withdraw after the job was given to someone else
+            user.pending_jobs = user.pending_jobs.saturating_sub(1);
+        }
+        job.bid_count = job.bid_count.saturating_sub(1);
         job.updated_at = clock.unix_timestamp;
-        user.pending_jobs += 1;
+        
+        emit!(ProposalWithdrawn {
+            job_id: job.job_id,
+            freelancer,
+            timestamp: clock.unix_timestamp,
+        });
         
         Ok(())
     }
@@ -310,7 +929,6 @@ pub mod backend {
         work_description: String,
     ) -> Result<()> {
         let job = &mut ctx.accounts.job;
-        let freelancer_user = &mut ctx.accounts.freelancer_user;
         let clock = Clock::get()?;
         
         // Validations
@@ -322,6 +940,10 @@ pub mod backend {
             job.freelancer == Some(ctx.accounts.freelancer.key()),
             ErrorCode::NotAssignedFreelancer
         );
+        require!(
+            job.milestones.is_empty(),
+            ErrorCode::JobHasMilestones
+        );
         require!(
             work_url.len() <= 500,
             ErrorCode::UrlTooLong
@@ -338,12 +960,14 @@ pub mod backend {
         job.work_submitted_at = Some(clock.unix_timestamp);
         job.updated_at = clock.unix_timestamp;
         job.revision_request = None;
+        job.revision_requested_at = None;
         
-        msg!(
-            "Work submitted for job '{}' by freelancer: {}",
-            job.title,
-            ctx.accounts.freelancer.key()
-        );
+        emit!(WorkSubmitted {
+            job_id: job.job_id,
+            freelancer: ctx.accounts.freelancer.key(),
+            milestone_index: None,
+            timestamp: clock.unix_timestamp,
+        });
         
         Ok(())
     }
@@ -368,6 +992,15 @@ pub mod backend {
             job.work_submitted,
             ErrorCode::NoWorkSubmitted
         );
+        require!(
+            job.milestones.is_empty(),
+            ErrorCode::JobHasMilestones
+        );
+        // Past the grace period the client settles through reclaim or a dispute
+        require!(
+            clock.unix_timestamp <= job.deadline.saturating_add(ctx.accounts.config.deadline_grace_period),
+            ErrorCode::RevisionWindowClosed
+        );
         require!(
             revision_description.len() <= 500,
             ErrorCode::DescriptionTooLong
@@ -376,20 +1009,22 @@ pub mod backend {
         job.work_submission_url = String::new();
         job.work_submission_description = String::new();
         job.revision_request = Some(revision_description);
+        job.revision_requested_at = Some(clock.unix_timestamp);
         job.updated_at = clock.unix_timestamp;
         
-        msg!(
-            "Revision requested for job '{}': {}",
-            job.title,
-            job.revision_request.as_ref().unwrap()
-        );
+        emit!(RevisionRequested {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: job.freelancer.unwrap_or_default(),
+            milestone_index: None,
+            timestamp: clock.unix_timestamp,
+        });
         
         Ok(())
     }
     pub fn accept_work(
         ctx: Context<AcceptWork>,
         _job_id: u64,
-        freelancer_rating: u8,
     ) -> Result<()> {
         let job = &mut ctx.accounts.job;
         let client_user = &mut ctx.accounts.client_user;
@@ -410,43 +1045,32 @@ pub mod backend {
             ErrorCode::NoWorkSubmitted
         );
         require!(
-            freelancer_rating >= 1 && freelancer_rating <= 5,
-            ErrorCode::InvalidRating
+            job.milestones.is_empty(),
+            ErrorCode::JobHasMilestones
         );
         
-        // Calculate reputation points (5 stars = 25, 4 stars = 20, etc.)
-        let reputation_points = (freelancer_rating as u64) * 5;
-        
         // Update job status
         job.status = JobStatus::Completed;
         job.work_approved = true;
         job.work_approved_at = Some(clock.unix_timestamp);
         job.updated_at = clock.unix_timestamp;
         
+        let half_life = ctx.accounts.config.reputation_half_life;
+        freelancer_user.touch(clock.unix_timestamp, half_life);
+        client_user.touch(clock.unix_timestamp, half_life);
+        
         // Update freelancer stats
         freelancer_user.completed_jobs = freelancer_user
             .completed_jobs
             .checked_add(1)
             .unwrap();
-        freelancer_user.reputation = freelancer_user
-            .reputation
-            .checked_add(reputation_points)
-            .unwrap();
-        freelancer_user.reputation = freelancer_user
-            .reputation
-            .checked_add(3)
-            .unwrap();
         freelancer_user.pending_jobs = freelancer_user
             .pending_jobs
             .checked_sub(1)
             .unwrap();
+        freelancer_user.record_delivery(delivered_on_time(job));
         
         // Update client stats
-        client_user.reputation = client_user
-            .reputation
-            .checked_add(5)
-            .unwrap();
-        
         client_user.completed_jobs = client_user
             .completed_jobs
             .checked_add(1)
@@ -460,23 +1084,283 @@ pub mod backend {
             .checked_add(job.budget)
             .unwrap();
         
-        msg!(
-            "Job '{}' completed! Freelancer rated {} stars (+{} reputation)",
-            job.title,
-            freelancer_rating,
-            reputation_points
-        );
+        emit!(WorkAccepted {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: freelancer_user.authority,
+            milestone_index: None,
+            amount: job.budget,
+            auto_approved: false,
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
+        _job_id: u64,
+        milestone_index: u8,
+        work_url: String,
+        work_description: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let clock = Clock::get()?;
+        let index = milestone_index as usize;
+        
+        // Validations
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::NotAssignedFreelancer
+        );
+        require!(
+            !job.milestones.is_empty(),
+            ErrorCode::NoMilestones
+        );
+        require!(
+            index < job.milestones.len(),
+            ErrorCode::InvalidMilestoneIndex
+        );
+        // Milestones are delivered in order
+        require!(
+            current_milestone(&job.milestones) == Some(index),
+            ErrorCode::MilestoneOutOfOrder
+        );
+        require!(
+            work_url.len() <= 500,
+            ErrorCode::UrlTooLong
+        );
+        require!(
+            work_description.len() <= 1000,
+            ErrorCode::DescriptionTooLong
+        );
+        
+        let milestone = &mut job.milestones[index];
+        require!(
+            milestone.status == MilestoneStatus::Pending
+                || milestone.status == MilestoneStatus::RevisionRequested,
+            ErrorCode::MilestoneNotOpen
+        );
+        milestone.status = MilestoneStatus::Submitted;
+        milestone.submitted_at = Some(clock.unix_timestamp);
+        
+        // The job-level submission fields track the milestone under review
+        job.work_submitted = true;
+        job.work_submission_url = work_url;
+        job.work_submission_description = work_description;
+        job.work_submitted_at = Some(clock.unix_timestamp);
+        job.updated_at = clock.unix_timestamp;
+        job.revision_request = None;
+        job.revision_requested_at = None;
+        
+        emit!(WorkSubmitted {
+            job_id: job.job_id,
+            freelancer: ctx.accounts.freelancer.key(),
+            milestone_index: Some(milestone_index),
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    pub fn request_milestone_revision(
+        ctx: Context<RequestMilestoneRevision>,
+        _job_id: u64,
+        milestone_index: u8,
+        revision_description: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let clock = Clock::get()?;
+        let index = milestone_index as usize;
+        
+        // Validations
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        require!(
+            index < job.milestones.len(),
+            ErrorCode::InvalidMilestoneIndex
+        );
+        require!(
+            job.milestones[index].status == MilestoneStatus::Submitted,
+            ErrorCode::MilestoneNotSubmitted
+        );
+        // Past the grace period the client settles through reclaim or a dispute
+        require!(
+            clock.unix_timestamp <= job.deadline.saturating_add(ctx.accounts.config.deadline_grace_period),
+            ErrorCode::RevisionWindowClosed
+        );
+        require!(
+            revision_description.len() <= 500,
+            ErrorCode::DescriptionTooLong
+        );
+        
+        job.milestones[index].status = MilestoneStatus::RevisionRequested;
+        job.work_submitted = false;
+        job.work_submission_url = String::new();
+        job.work_submission_description = String::new();
+        job.revision_request = Some(revision_description);
+        job.revision_requested_at = Some(clock.unix_timestamp);
+        job.updated_at = clock.unix_timestamp;
+        
+        emit!(RevisionRequested {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: job.freelancer.unwrap_or_default(),
+            milestone_index: Some(milestone_index),
+            timestamp: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    pub fn approve_milestone(
+        ctx: Context<ApproveMilestone>,
+        _job_id: u64,
+        milestone_index: u8,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_FUNDS)?;
+        let job = &mut ctx.accounts.job;
+        let client_user = &mut ctx.accounts.client_user;
+        let freelancer_user = &mut ctx.accounts.freelancer_user;
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        let index = milestone_index as usize;
+        
+        // Validations
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::InvalidFreelancerAccount
+        );
+        require!(
+            index < job.milestones.len(),
+            ErrorCode::InvalidMilestoneIndex
+        );
+        require!(
+            job.milestones[index].status == MilestoneStatus::Submitted,
+            ErrorCode::MilestoneNotSubmitted
+        );
+        
+        let milestone = &mut job.milestones[index];
+        milestone.status = MilestoneStatus::Approved;
+        milestone.approved_at = Some(now);
+        let milestone_amount = milestone.amount;
+        let all_approved = job
+            .milestones
+            .iter()
+            .all(|m| m.status == MilestoneStatus::Approved);
+        
+        // The last milestone sweeps whatever is left in escrow
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let escrow_balance = escrow.balance()?;
+        let release_amount = if all_approved {
+            escrow_balance
+        } else {
+            milestone_amount.min(escrow_balance)
+        };
+        let fee = ctx.accounts.config.fee_for(release_amount);
+        escrow.release_with_fee(
+            &ctx.accounts.freelancer,
+            ctx.accounts.freelancer_token.as_ref(),
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            release_amount,
+            fee,
+        )?;
+        
+        job.total_paid = job
+            .total_paid
+            .checked_add(milestone_amount)
+            .unwrap();
+        job.work_submitted = false;
+        job.updated_at = now;
+        
+        freelancer_user.total_earnings = freelancer_user
+            .total_earnings
+            .checked_add(milestone_amount)
+            .unwrap();
+        client_user.total_spent = client_user
+            .total_spent
+            .checked_add(milestone_amount)
+            .unwrap();
+        
+        if all_approved {
+            job.status = JobStatus::Completed;
+            job.work_approved = true;
+            job.work_approved_at = Some(now);
+            job.escrow = Pubkey::default();
+            
+            let half_life = ctx.accounts.config.reputation_half_life;
+            freelancer_user.touch(now, half_life);
+            client_user.touch(now, half_life);
+            freelancer_user.completed_jobs = freelancer_user
+                .completed_jobs
+                .checked_add(1)
+                .unwrap();
+            freelancer_user.pending_jobs = freelancer_user
+                .pending_jobs
+                .checked_sub(1)
+                .unwrap();
+            freelancer_user.record_delivery(delivered_on_time(job));
+            
+            client_user.completed_jobs = client_user
+                .completed_jobs
+                .checked_add(1)
+                .unwrap();
+            client_user.pending_jobs = client_user
+                .pending_jobs
+                .checked_sub(1)
+                .unwrap();
+        }
+        
+        emit!(WorkAccepted {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: freelancer_user.authority,
+            milestone_index: Some(milestone_index),
+            amount: milestone_amount,
+            auto_approved: false,
+            timestamp: now,
+        });
+        emit!(EscrowReleased {
+            job_id: job.job_id,
+            recipient: freelancer_user.authority,
+            amount: release_amount,
+            fee,
+            timestamp: now,
+        });
         
         Ok(())
     }
     pub fn withdraw_from_escrow(
         ctx: Context<WithdrawFromEscrow>,
         _job_id: u64,
-        client_rating: u8,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_FUNDS)?;
         let job = &mut ctx.accounts.job;
         let freelancer_user = &mut ctx.accounts.freelancer_user;
-        let client_user = &mut ctx.accounts.client_user;
         
         // Validations
         require!(
@@ -491,48 +1375,32 @@ pub mod backend {
             job.total_paid == 0,
             ErrorCode::AlreadyWithdrawn
         );
-        require!(
-            client_rating >= 1 && client_rating <= 5,
-            ErrorCode::InvalidRating
-        );
-        
-        // Calculate reputation points for client
-        let reputation_points = (client_rating as u64) * 5;
         
         // Transfer funds from escrow to freelancer
-        let escrow_lamports = ctx.accounts.escrow.lamports();
-        let job_key = job.key();
-        let seeds = &[
-            b"escrow",
-            job_key.as_ref(),
-            &[ctx.bumps.escrow],
-        ];
-        let signer_seeds = &[&seeds[..]];
-        
-        // Transfer funds from escrow to freelancer using CPI
-        let transfer_ix = system_instruction::transfer(
-            &ctx.accounts.escrow.key(),
-            &ctx.accounts.freelancer.key(),
-            escrow_lamports,
-        );
-        
-        anchor_lang::solana_program::program::invoke_signed(
-            &transfer_ix,
-            &[
-                ctx.accounts.escrow.to_account_info(),
-                ctx.accounts.freelancer.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            signer_seeds,
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let escrow_balance = escrow.balance()?;
+        let fee = ctx.accounts.config.fee_for(escrow_balance);
+        escrow.release_with_fee(
+            &ctx.accounts.freelancer.to_account_info(),
+            ctx.accounts.freelancer_token.as_ref(),
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            escrow_balance,
+            fee,
         )?;
         
-        // Update client reputation
-        client_user.reputation = client_user
-            .reputation
-            .checked_add(reputation_points)
-            .unwrap();
+        let now = Clock::get()?.unix_timestamp;
         
         // Update freelancer earnings
+        freelancer_user.touch(now, ctx.accounts.config.reputation_half_life);
         freelancer_user.total_earnings = freelancer_user
             .total_earnings
             .checked_add(job.budget)
@@ -542,43 +1410,628 @@ pub mod backend {
         
         job.total_paid = job.budget;
         job.escrow = Pubkey::default();
+        job.updated_at = now;
+        
+        emit!(EscrowReleased {
+            job_id: job.job_id,
+            recipient: ctx.accounts.freelancer.key(),
+            amount: escrow_balance,
+            fee,
+            timestamp: job.updated_at,
+        });
+        
+        Ok(())
+    }
+    pub fn consent_to_cancellation(
+        ctx: Context<ConsentToCancellation>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::NotAssignedFreelancer
+        );
+        
+        job.cancellation_consent = true;
         job.updated_at = Clock::get()?.unix_timestamp;
         
-        msg!(
-            "Freelancer withdrew {} lamports from escrow. Client rated {} stars (+{} reputation)",
-            escrow_lamports,
-            client_rating,
-            reputation_points
+        emit!(CancellationConsented {
+            job_id: job.job_id,
+            freelancer: ctx.accounts.freelancer.key(),
+            timestamp: job.updated_at,
+        });
+        
+        Ok(())
+    }
+    pub fn cancel_job(
+        ctx: Context<CancelJob>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let client_user = &mut ctx.accounts.client_user;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
         );
         
+        let mut refunded = 0;
+        match job.status {
+            JobStatus::Open => {
+                // Nothing is escrowed yet
+                client_user.active_jobs = client_user.active_jobs.saturating_sub(1);
+            }
+            JobStatus::InProgress => {
+                // Once work has been delivered the freelancer has to agree
+                require!(
+                    job.work_submitted_at.is_none() || job.cancellation_consent,
+                    ErrorCode::CancellationConsentRequired
+                );
+                let freelancer_user = ctx
+                    .accounts
+                    .freelancer_user
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidFreelancerAccount)?;
+                require!(
+                    job.freelancer == Some(freelancer_user.authority),
+                    ErrorCode::InvalidFreelancerAccount
+                );
+                
+                // Refund whatever is still in escrow to the client
+                let escrow = Escrow::job(
+                    job.key(),
+                    job.mint,
+                    &ctx.accounts.escrow,
+                    ctx.bumps.escrow,
+                    ctx.accounts.escrow_token.as_ref(),
+                    ctx.accounts.token_program.as_ref(),
+                    &ctx.accounts.system_program,
+                );
+                refunded = escrow.balance()?;
+                escrow.release(
+                    &ctx.accounts.client.to_account_info(),
+                    ctx.accounts.client_token.as_ref(),
+                    refunded,
+                )?;
+                
+                freelancer_user.pending_jobs = freelancer_user.pending_jobs.saturating_sub(1);
+                freelancer_user.cancelled_jobs = freelancer_user
+                    .cancelled_jobs
+                    .checked_add(1)
+                    .unwrap();
+                client_user.pending_jobs = client_user.pending_jobs.saturating_sub(1);
+                job.escrow = Pubkey::default();
+            }
+            _ => return err!(ErrorCode::JobNotCancellable),
+        }
+        
+        client_user.cancelled_jobs = client_user
+            .cancelled_jobs
+            .checked_add(1)
+            .unwrap();
+        job.status = JobStatus::Cancelled;
+        job.updated_at = now;
+        
+        emit!(JobCancelled {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: job.freelancer,
+            refunded,
+            missed_deadline: false,
+            timestamp: now,
+        });
+        
         Ok(())
     }
-    pub fn delete_job(
-        ctx: Context<DeleteJob>,
+    pub fn reclaim_after_deadline(
+        ctx: Context<ReclaimAfterDeadline>,
         _job_id: u64,
     ) -> Result<()> {
-        let job = &ctx.accounts.job;
+        let job = &mut ctx.accounts.job;
+        let client_user = &mut ctx.accounts.client_user;
+        let freelancer_user = &mut ctx.accounts.freelancer_user;
+        let now = Clock::get()?.unix_timestamp;
         
-       
+        // Validations
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        require!(
+            now > job.deadline.saturating_add(ctx.accounts.config.deadline_grace_period),
+            ErrorCode::DeadlineNotPassed
+        );
+        // A delivery waiting for review has to be handled through the normal flow
+        require!(
+            !job.work_submitted,
+            ErrorCode::WorkAlreadySubmitted
+        );
+        // After a revision request the freelancer gets a full grace period to resubmit
+        if let Some(requested_at) = job.revision_requested_at {
+            require!(
+                now > requested_at.saturating_add(ctx.accounts.config.deadline_grace_period),
+                ErrorCode::RevisionWindowOpen
+            );
+        }
+        
+        // Refund whatever is still in escrow to the client
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let refunded = escrow.balance()?;
+        escrow.release(
+            &ctx.accounts.client.to_account_info(),
+            ctx.accounts.client_token.as_ref(),
+            refunded,
+        )?;
+        
+        let half_life = ctx.accounts.config.reputation_half_life;
+        freelancer_user.touch(now, half_life);
+        client_user.touch(now, half_life);
+        
+        // Not delivering at all counts against the on-time record
+        freelancer_user.record_delivery(false);
+        freelancer_user.pending_jobs = freelancer_user.pending_jobs.saturating_sub(1);
+        freelancer_user.cancelled_jobs = freelancer_user
+            .cancelled_jobs
+            .checked_add(1)
+            .unwrap();
+        
+        client_user.pending_jobs = client_user.pending_jobs.saturating_sub(1);
+        client_user.cancelled_jobs = client_user
+            .cancelled_jobs
+            .checked_add(1)
+            .unwrap();
+        
+        job.status = JobStatus::Cancelled;
+        job.escrow = Pubkey::default();
+        job.updated_at = now;
+        
+        emit!(JobCancelled {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: job.freelancer,
+            refunded,
+            missed_deadline: true,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Lets the freelancer approve their own submission and get paid when the
+    /// client has not reviewed it within the configured review period.
+    pub fn claim_after_review_period(
+        ctx: Context<ClaimAfterReviewPeriod>,
+        _job_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_FUNDS)?;
+        let job = &mut ctx.accounts.job;
+        let freelancer_user = &mut ctx.accounts.freelancer_user;
+        let client_user = &mut ctx.accounts.client_user;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Validations
+        require!(
+            job.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            job.freelancer == Some(ctx.accounts.freelancer.key()),
+            ErrorCode::NotAssignedFreelancer
+        );
+        require!(
+            job.work_submitted,
+            ErrorCode::NoWorkSubmitted
+        );
+        let submitted_at = job.work_submitted_at.ok_or(ErrorCode::NoWorkSubmitted)?;
+        require!(
+            now >= submitted_at.saturating_add(ctx.accounts.config.review_period),
+            ErrorCode::ReviewPeriodActive
+        );
+        
+        // Approve the submission: the milestone under review, or the whole job
+        let milestone_index = current_milestone(&job.milestones);
+        let amount = match milestone_index {
+            Some(index) => {
+                let milestone = &mut job.milestones[index];
+                require!(
+                    milestone.status == MilestoneStatus::Submitted,
+                    ErrorCode::MilestoneNotSubmitted
+                );
+                milestone.status = MilestoneStatus::Approved;
+                milestone.approved_at = Some(now);
+                milestone.amount
+            }
+            None => job.budget - job.total_paid,
+        };
+        let completed = current_milestone(&job.milestones).is_none();
+        
+        // The final release sweeps whatever is left in escrow
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let escrow_balance = escrow.balance()?;
+        let release_amount = if completed {
+            escrow_balance
+        } else {
+            amount.min(escrow_balance)
+        };
+        let fee = ctx.accounts.config.fee_for(release_amount);
+        escrow.release_with_fee(
+            &ctx.accounts.freelancer.to_account_info(),
+            ctx.accounts.freelancer_token.as_ref(),
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            release_amount,
+            fee,
+        )?;
+        
+        job.total_paid = job.total_paid.checked_add(amount).unwrap();
+        job.work_submitted = false;
+        job.updated_at = now;
+        freelancer_user.total_earnings = freelancer_user
+            .total_earnings
+            .checked_add(amount)
+            .unwrap();
+        client_user.total_spent = client_user
+            .total_spent
+            .checked_add(amount)
+            .unwrap();
+        
+        if completed {
+            job.status = JobStatus::Completed;
+            job.work_approved = true;
+            job.work_approved_at = Some(now);
+            job.escrow = Pubkey::default();
+            
+            let half_life = ctx.accounts.config.reputation_half_life;
+            freelancer_user.touch(now, half_life);
+            client_user.touch(now, half_life);
+            freelancer_user.completed_jobs = freelancer_user
+                .completed_jobs
+                .checked_add(1)
+                .unwrap();
+            freelancer_user.pending_jobs = freelancer_user
+                .pending_jobs
+                .checked_sub(1)
+                .unwrap();
+            freelancer_user.record_delivery(delivered_on_time(job));
+            client_user.completed_jobs = client_user
+                .completed_jobs
+                .checked_add(1)
+                .unwrap();
+            client_user.pending_jobs = client_user
+                .pending_jobs
+                .checked_sub(1)
+                .unwrap();
+        }
+        
+        emit!(WorkAccepted {
+            job_id: job.job_id,
+            client: job.client,
+            freelancer: ctx.accounts.freelancer.key(),
+            milestone_index: milestone_index.map(|index| index as u8),
+            amount,
+            auto_approved: true,
+            timestamp: now,
+        });
+        emit!(EscrowReleased {
+            job_id: job.job_id,
+            recipient: ctx.accounts.freelancer.key(),
+            amount: release_amount,
+            fee,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Leaves a rating and comment on the other party of a completed job.
+    /// Each side can review a job once.
+    pub fn submit_review(
+        ctx: Context<SubmitReview>,
+        _job_id: u64,
+        rating: u8,
+        comment: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let reviewee_user = &mut ctx.accounts.reviewee_user;
+        let reviewer = ctx.accounts.reviewer.key();
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Validations
         require!(
             job.status == JobStatus::Completed,
             ErrorCode::JobNotCompleted
         );
+        let reviewee = if job.client == reviewer {
+            job.freelancer.ok_or(ErrorCode::NoFreelancerAssigned)?
+        } else if job.freelancer == Some(reviewer) {
+            job.client
+        } else {
+            return err!(ErrorCode::UnauthorizedUser);
+        };
+        require!(
+            reviewee_user.authority == reviewee,
+            ErrorCode::InvalidRevieweeAccount
+        );
         require!(
-            job.total_paid == job.budget,
-            ErrorCode::PaymentNotCompleted
+            (1..=5).contains(&rating),
+            ErrorCode::InvalidRating
         );
+        require!(
+            comment.len() <= MAX_REVIEW_LENGTH,
+            ErrorCode::ReviewTooLong
+        );
+        require!(
+            !job.reviews.iter().any(|review| review.reviewer == reviewer),
+            ErrorCode::AlreadyReviewed
+        );
+        
+        job.reviews.push(Review {
+            reviewer,
+            rating,
+            comment,
+            created_at: now,
+            reply: None,
+            replied_at: None,
+        });
+        job.updated_at = now;
+        
+        reviewee_user.touch(now, ctx.accounts.config.reputation_half_life);
+        reviewee_user.record_rating(rating, job.client == reviewer);
+        
+        emit!(ReviewSubmitted {
+            job_id: job.job_id,
+            reviewer,
+            reviewee,
+            rating,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Posts the reviewed party's public reply to a review, once.
+    pub fn reply_to_review(
+        ctx: Context<ReplyToReview>,
+        _job_id: u64,
+        reviewer: Pubkey,
+        reply: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let author = ctx.accounts.author.key();
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            reply.len() <= MAX_REPLY_LENGTH,
+            ErrorCode::ReviewTooLong
+        );
+        require!(
+            author != reviewer && (job.client == author || job.freelancer == Some(author)),
+            ErrorCode::NotReviewedParty
+        );
+        let review = job
+            .reviews
+            .iter_mut()
+            .find(|review| review.reviewer == reviewer)
+            .ok_or(ErrorCode::ReviewNotFound)?;
+        require!(review.reply.is_none(), ErrorCode::AlreadyReplied);
+        
+        review.reply = Some(reply);
+        review.replied_at = Some(now);
+        job.updated_at = now;
+        
+        emit!(ReviewReplied {
+            job_id: job.job_id,
+            reviewer,
+            reviewee: author,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Settles a juror's stake once the dispute is resolved. Majority jurors get
+    /// their stake back plus a pro rata share of the reward pool, minority
+    /// and unrevealed jurors are slashed and only recover the vote record's rent.
+    pub fn claim_juror_stake(
+        ctx: Context<ClaimJurorStake>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let job_key = job.key();
+        let vote_record = &ctx.accounts.vote_record;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Stakes settle once the dispute is resolved, the filing fee may
+        // still be added to an earlier round's pool until then
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(
+            dispute.status == DisputeStatus::Resolved,
+            ErrorCode::DisputeNotResolved
+        );
+        let stake_mint = dispute.stake_mint;
+        let ruling = dispute
+            .rulings
+            .get_mut((vote_record.round as usize).saturating_sub(1))
+            .ok_or(ErrorCode::DisputeNotResolved)?;
+        
+        let coherent = ruling.coherent_brackets == 0
+            || (vote_record.revealed
+                && ruling.coherent_brackets & (1 << vote_record.bracket) != 0);
+        let mut payout = 0;
+        // Nothing is left to pay once the round's payouts were swept
+        if coherent && ruling.unclaimed_payouts > 0 {
+            let vault = Escrow::dispute_vault(
+                job_key,
+                stake_mint,
+                &ctx.accounts.dispute_vault,
+                ctx.bumps.dispute_vault,
+                ctx.accounts.dispute_vault_token.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+                &ctx.accounts.system_program,
+            );
+            // The last claim of the round takes the rounding dust
+            payout = if ruling.unclaimed_payouts == 1 {
+                ruling.unclaimed_amount
+            } else {
+                let share = (ruling.reward_pool as u128) * (vote_record.stake as u128)
+                    / (ruling.coherent_stake as u128);
+                vote_record.stake.checked_add(share as u64).unwrap()
+            };
+            ruling.unclaimed_payouts = ruling.unclaimed_payouts.saturating_sub(1);
+            ruling.unclaimed_amount = ruling.unclaimed_amount.saturating_sub(payout);
+            vault.release(
+                &ctx.accounts.voter.to_account_info(),
+                ctx.accounts.voter_token.as_ref(),
+                payout,
+            )?;
+        }
+        
+        emit!(JurorStakeClaimed {
+            job_id: job.job_id,
+            voter: vote_record.voter,
+            stake: vote_record.stake,
+            payout,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    pub fn delete_job(
+        ctx: Context<DeleteJob>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &ctx.accounts.job;
+        
+       
+        // Cancelled jobs have already had their escrow refunded
+        if job.status != JobStatus::Cancelled {
+            require!(
+                job.status == JobStatus::Completed,
+                ErrorCode::JobNotCompleted
+            );
+            require!(
+                job.total_paid == job.budget,
+                ErrorCode::PaymentNotCompleted
+            );
+        }
         require!(
             job.client == ctx.accounts.authority.key(),
             ErrorCode::NotJobClient
         );
+        // Jurors settle their stakes against the dispute stored in the job
+        if let Some(dispute) = &job.dispute {
+            require!(
+                dispute.rulings.iter().all(|ruling| ruling.unclaimed_payouts == 0),
+                ErrorCode::StakesUnclaimed
+            );
+        }
+        
+        emit!(JobDeleted {
+            job_id: job.job_id,
+            client: job.client,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    /// Moves juror payouts that were not claimed within the stake claim
+    /// period to the treasury, so the client can delete the job.
+    pub fn sweep_juror_stakes(
+        ctx: Context<SweepJurorStakes>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let job_key = job.key();
+        let now = Clock::get()?.unix_timestamp;
         
-        msg!(
-            "Job '{}' (ID: {}) deleted successfully. Rent refunded to client.",
-            job.title,
-            job.job_id
+        require!(
+            job.client == ctx.accounts.client.key(),
+            ErrorCode::NotJobClient
+        );
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        let resolved_at = dispute.resolved_at.ok_or(ErrorCode::DisputeNotResolved)?;
+        require!(
+            now >= resolved_at.saturating_add(ctx.accounts.config.stake_claim_period),
+            ErrorCode::StakeClaimPeriodOpen
         );
         
+        let mut swept: u64 = 0;
+        for ruling in dispute.rulings.iter_mut() {
+            swept = swept.checked_add(ruling.unclaimed_amount).unwrap();
+            ruling.unclaimed_payouts = 0;
+            ruling.unclaimed_amount = 0;
+        }
+        let vault = Escrow::dispute_vault(
+            job_key,
+            dispute.stake_mint,
+            &ctx.accounts.dispute_vault,
+            ctx.bumps.dispute_vault,
+            ctx.accounts.dispute_vault_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        vault.release(
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            swept,
+        )?;
+        
+        emit!(JurorStakesSwept {
+            job_id: job.job_id,
+            amount: swept,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Returns the rent of an evidence record to its submitter once the job
+    /// has been deleted.
+    pub fn close_evidence(
+        _ctx: Context<CloseEvidence>,
+        _job_id: u64,
+        _index: u8,
+    ) -> Result<()> {
+        Ok(())
+    }
+    /// Returns the rent of a bid whose job has been deleted. A losing bid
+    /// still counted towards the freelancer's pending jobs.
+    pub fn close_bid(
+        ctx: Context<CloseBid>,
+        _job_id: u64,
+    ) -> Result<()> {
+        if !ctx.accounts.bid.selected {
+            let user = &mut ctx.accounts.user;
+            user.pending_jobs = user.pending_jobs.saturating_sub(1);
+        }
+        Ok(())
+    }
+    /// Returns the rent of a vote record whose job has been deleted.
+    pub fn close_vote_record(
+        _ctx: Context<CloseVoteRecord>,
+        _job_id: u64,
+    ) -> Result<()> {
         Ok(())
     }
     pub fn raise_dispute(
@@ -587,6 +2040,7 @@ pub mod backend {
         reason: String,
         voting_period: i64,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_DISPUTES)?;
         let job = &mut ctx.accounts.job;
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
@@ -605,14 +2059,43 @@ pub mod backend {
             reason.len() <= 500,
             ErrorCode::DescriptionTooLong
         );
+        // Voting leaves room for the reveal phase at the end
         require!(
-            voting_period > 0 && voting_period <= 7 * 24 * 60 * 60, // max 7 days
+            voting_period > ctx.accounts.config.reveal_period
+                && voting_period <= ctx.accounts.config.max_voting_period,
             ErrorCode::InvalidVotingPeriod
         );
         require!(
             job.dispute.is_none(),
             ErrorCode::DisputeAlreadyExists
         );
+        // Jurors stake in the configured currency, SPL stakes need a vault token account
+        let stake_mint = ctx.accounts.stake_mint.as_ref().map(|mint| mint.key());
+        require!(
+            stake_mint == ctx.accounts.config.stake_mint,
+            ErrorCode::InvalidStakeMint
+        );
+        require!(
+            stake_mint.is_none() || ctx.accounts.dispute_vault_token.is_some(),
+            ErrorCode::MissingTokenAccount
+        );
+    
+        // The filing fee is held in the dispute vault with the juror stakes
+        let filing_fee = ctx.accounts.config.dispute_fee;
+        let vault = Escrow::dispute_vault(
+            job.key(),
+            stake_mint,
+            &ctx.accounts.dispute_vault,
+            ctx.bumps.dispute_vault,
+            ctx.accounts.dispute_vault_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        vault.deposit(
+            &ctx.accounts.authority.to_account_info(),
+            ctx.accounts.raiser_token.as_ref(),
+            filing_fee,
+        )?;
     
         // Determine roles
         let (raiser_role, against, against_role) = if job.client == raiser {
@@ -638,41 +2121,215 @@ pub mod backend {
             created_at: now,
             resolved_at: None,
             resolution: None,
+            round: 1,
+            selection_slot: clock.slot + SELECTION_DELAY_SLOTS,
+            panel_selected: false,
+            panel: Vec::new(),
+            prior_jurors: Vec::new(),
             voting_start: now,
+            commit_end: now + voting_period - ctx.accounts.config.reveal_period,
             voting_end: now + voting_period,
-            votes_for_raiser: 0,
-            votes_for_against: 0,
-            voters: Vec::new(),
+            commit_count: 0,
+            votes: [0; RULING_BRACKETS],
+            extensions: 0,
             raiser_role,
             against_role,
+            stake_mint,
+            juror_stake: ctx.accounts.config.juror_stake,
+            stake_committed: 0,
+            stakes: [0; RULING_BRACKETS],
+            filing_fee,
+            rulings: Vec::new(),
+            appeal: None,
+            evidence: Vec::new(),
+            raiser_evidence: 0,
+            against_evidence: 0,
+        };
+    
+        job.dispute = Some(dispute);
+        job.status = JobStatus::Disputed;
+        job.updated_at = now;
+        let raiser_user = &mut ctx.accounts.raiser_user;
+        raiser_user.touch(now, ctx.accounts.config.reputation_half_life);
+        raiser_user.disputes_raised = raiser_user.disputes_raised.checked_add(1).unwrap();
+    
+        emit!(DisputeRaised {
+            job_id: job.job_id,
+            raiser,
+            against,
+            filing_fee,
+            commit_end: now + voting_period - ctx.accounts.config.reveal_period,
+            voting_end: now + voting_period,
+            timestamp: now,
+        });
+    
+        Ok(())  
+    }
+    /// Draws the jury panel for the current dispute round from the juror
+    /// registry, seeded by the hash of the round's selection slot, and starts
+    /// voting. Anyone can call this. Once that hash has left the SlotHashes
+    /// sysvar (about 512 slots) the draw is rescheduled to a new slot instead,
+    /// either party can prevent that by calling in time.
+    pub fn select_jurors(
+        ctx: Context<SelectJurors>,
+        _job_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_DISPUTES)?;
+        let job = &mut ctx.accounts.job;
+        let job_key = job.key();
+        let config = &ctx.accounts.config;
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        require!(!dispute.panel_selected, ErrorCode::PanelAlreadySelected);
+        
+        let Some(entropy) = panel_entropy(&ctx.accounts.slot_hashes, &job_key, dispute.selection_slot)? else {
+            dispute.selection_slot = clock.slot + SELECTION_DELAY_SLOTS;
+            let (round, selection_slot) = (dispute.round, dispute.selection_slot);
+            job.updated_at = now;
+            emit!(JurySelectionRescheduled {
+                job_id: job.job_id,
+                round,
+                selection_slot,
+                timestamp: now,
+            });
+            return Ok(());
+        };
+        let seats = if dispute.round > 1 { config.appeal_panel_size } else { config.panel_size };
+        let jurors = dispute.draw_panel(&ctx.accounts.juror_registry.jurors, seats as usize, &entropy);
+        dispute.panel_selected = true;
+        dispute.open_voting(now);
+        
+        let (round, commit_end, voting_end) = (dispute.round, dispute.commit_end, dispute.voting_end);
+        job.updated_at = now;
+        emit!(JurorsSelected {
+            job_id: job.job_id,
+            round,
+            jurors,
+            commit_end,
+            voting_end,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Gives up a panel seat the juror cannot vote from, because they worked
+    /// with one of the parties or no longer meet the round's requirements.
+    /// The seat is left empty without a suspension and refilled if the round
+    /// is extended.
+    pub fn recuse_juror(
+        ctx: Context<RecuseJuror>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let juror = ctx.accounts.juror.key();
+        let config = &ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        let index = dispute
+            .panel
+            .iter()
+            .position(|seat| seat.juror == juror && !seat.committed)
+            .ok_or(ErrorCode::NotOnJuryPanel)?;
+        
+        let juror_user = &mut ctx.accounts.juror_user;
+        juror_user.touch(now, config.reputation_half_life);
+        let cannot_vote = config.require_eligible_juror(juror_user, now, dispute.round).is_err()
+            || has_collaborated(&ctx.accounts.raiser_collaboration, &juror, &dispute.raiser)?
+            || has_collaborated(&ctx.accounts.against_collaboration, &juror, &dispute.against)?;
+        require!(cannot_vote, ErrorCode::JurorEligible);
+        dispute.panel.remove(index);
+        
+        let round = dispute.round;
+        job.updated_at = now;
+        emit!(JurorRecused {
+            job_id: job.job_id,
+            round,
+            juror,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+    /// Adds a piece of evidence from the raiser or the against party. `index`
+    /// is the next free slot, `dispute.evidence.len()`.
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        _job_id: u64,
+        index: u8,
+        uri: String,
+        content_hash: [u8; 32],
+        statement: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let job_key = job.key();
+        let submitter = ctx.accounts.submitter.key();
+        let now = Clock::get()?.unix_timestamp;
+        
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        require!(
+            submitter == dispute.raiser || submitter == dispute.against,
+            ErrorCode::UnauthorizedUser
+        );
+        // Jurors must be able to see the full record before committing
+        require!(now < dispute.commit_end, ErrorCode::EvidencePeriodOver);
+        // Each side gets half of the slots, so one party cannot crowd out the other
+        let submitted = if submitter == dispute.raiser {
+            &mut dispute.raiser_evidence
+        } else {
+            &mut dispute.against_evidence
         };
-    
-        job.dispute = Some(dispute);
-        job.status = JobStatus::Disputed;
-        job.updated_at = now;
-        let raiser_user = &mut ctx.accounts.raiser_user;
-        raiser_user.disputes_raised = raiser_user.disputes_raised.checked_add(1).unwrap();
-    
-        msg!(
-            "Dispute raised for job '{}'. {} (raiser) vs {} (against). Voting ends at: {}",
-            job.title,
-            raiser,
-            against,
-            now + voting_period
+        require!(*submitted < MAX_EVIDENCE_PER_PARTY, ErrorCode::MaxEvidenceReached);
+        *submitted += 1;
+        require!(
+            index as usize == dispute.evidence.len(),
+            ErrorCode::InvalidEvidenceIndex
         );
-    
-        Ok(())  
+        require!(uri.len() <= MAX_EVIDENCE_URI_LENGTH, ErrorCode::EvidenceUriTooLong);
+        require!(
+            statement.len() <= MAX_EVIDENCE_STATEMENT_LENGTH,
+            ErrorCode::EvidenceStatementTooLong
+        );
+        
+        let evidence = &mut ctx.accounts.evidence;
+        evidence.job = job_key;
+        evidence.submitter = submitter;
+        evidence.index = index;
+        evidence.uri = uri;
+        evidence.content_hash = content_hash;
+        evidence.statement = statement;
+        evidence.created_at = now;
+        evidence.bump = ctx.bumps.evidence;
+        dispute.evidence.push(evidence.key());
+        
+        emit!(EvidenceSubmitted {
+            job_id: job.job_id,
+            submitter,
+            evidence: evidence.key(),
+            index,
+            content_hash,
+            timestamp: now,
+        });
+        
+        Ok(())
     }
     // ============================================================================
     // CORRECTED VOTE_DISPUTE FUNCTION
     // ============================================================================
+    /// Commits a juror's secret ballot, `VoteRecord::commitment_for(job, voter, round, bracket, salt)`,
+    /// and locks their stake. The vote is revealed with `reveal_vote`.
     pub fn vote_dispute(
         ctx: Context<VoteDispute>,
         _job_id: u64,
-        vote_for_raiser: bool,
+        commitment: [u8; 32],
     ) -> Result<()> {
         let job = &mut ctx.accounts.job;
-        let voter_user = &mut ctx.accounts.voter_user;
+        let job_key = job.key();
         let voter = ctx.accounts.voter.key();
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
@@ -681,10 +2338,10 @@ pub mod backend {
         let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
         require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
     
-        // Ensure voting window is active
+        // Ensure the commit phase is active
         require!(
-            now >= dispute.voting_start && now <= dispute.voting_end, 
-            ErrorCode::VotingClosed
+            now >= dispute.voting_start && now < dispute.commit_end,
+            ErrorCode::CommitPhaseOver
         );
     
         // FIX 1: Verify voter is not involved in the dispute
@@ -692,55 +2349,282 @@ pub mod backend {
             voter != dispute.raiser && voter != dispute.against,
             ErrorCode::CannotVoteOwnDispute
         );
+        
+        // Only jurors drawn for this round's panel can vote
+        let seat = dispute
+            .panel
+            .iter_mut()
+            .find(|seat| seat.juror == voter && !seat.committed)
+            .ok_or(ErrorCode::NotOnJuryPanel)?;
+        seat.committed = true;
+        
+        // Jurors need an established account and no history with either party
+        let voter_user = &mut ctx.accounts.voter_user;
+        voter_user.touch(now, ctx.accounts.config.reputation_half_life);
+        ctx.accounts.config.require_eligible_juror(voter_user, now, dispute.round)?;
+        require_no_collaboration(&ctx.accounts.raiser_collaboration, &voter, &dispute.raiser)?;
+        require_no_collaboration(&ctx.accounts.against_collaboration, &voter, &dispute.against)?;
     
-        // Ensure voter hasn't voted before
-        require!(
-            !dispute.voters.iter().any(|v| v == &voter),
-            ErrorCode::AlreadyVoted
-        );
-    
-        // FIX 2: Add a check to ensure the voters vector doesn't grow too large
+        // The vote record PDA can only be created once per voter
         require!(
-            dispute.voters.len() < 100,
+            dispute.commit_count < ctx.accounts.config.max_voters,
             ErrorCode::MaxVotersReached
         );
     
-        // Record vote
-        if vote_for_raiser {
-            dispute.votes_for_raiser = dispute.votes_for_raiser.checked_add(1).unwrap();
-        } else {
-            dispute.votes_for_against = dispute.votes_for_against.checked_add(1).unwrap();
-        }
-        dispute.voters.push(voter);
+        // Lock the juror's stake in the dispute vault
+        let stake = dispute.juror_stake;
+        let vault = Escrow::dispute_vault(
+            job_key,
+            dispute.stake_mint,
+            &ctx.accounts.dispute_vault,
+            ctx.bumps.dispute_vault,
+            ctx.accounts.dispute_vault_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        vault.deposit(
+            &ctx.accounts.voter.to_account_info(),
+            ctx.accounts.voter_token.as_ref(),
+            stake,
+        )?;
     
-        // Give voter +3 reputation
-        voter_user.reputation = voter_user.reputation.checked_add(3).unwrap();
+        // Record the commitment, the vote itself stays secret until revealed
+        dispute.commit_count = dispute.commit_count.checked_add(1).unwrap();
+        dispute.stake_committed = dispute.stake_committed.checked_add(stake).unwrap();
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.job = job_key;
+        vote_record.voter = voter;
+        vote_record.commitment = commitment;
+        vote_record.round = dispute.round;
+        vote_record.revealed = false;
+        vote_record.bracket = 0;
+        vote_record.stake = stake;
+        vote_record.created_at = now;
+        vote_record.bump = ctx.bumps.vote_record;
     
-        msg!(
-            "Voter {} voted for {} on dispute for job {}",
+        emit!(VoteCast {
+            job_id: job.job_id,
             voter,
-            if vote_for_raiser { "raiser" } else { "against" },
-            job.job_id
+            timestamp: now,
+        });
+    
+        Ok(())
+    }
+    /// Opens a committed vote. Only revealed votes count in `finalize_dispute`,
+    /// stakes of unrevealed commits are slashed.
+    pub fn reveal_vote(
+        ctx: Context<RevealVote>,
+        _job_id: u64,
+        bracket: u8,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        require!(dispute.panel_selected, ErrorCode::PanelNotSelected);
+        require!(
+            vote_record.round == dispute.round
+                && now >= dispute.commit_end
+                && now < dispute.voting_end,
+            ErrorCode::RevealPhaseNotActive
+        );
+        require!(!vote_record.revealed, ErrorCode::AlreadyRevealed);
+        // An under-quorum round has to be extended before anything is revealed
+        let config = &ctx.accounts.config;
+        require!(
+            !dispute.needs_extension(config.quorum_for(dispute.round), config.max_voting_extensions),
+            ErrorCode::QuorumNotReached
+        );
+        require!(
+            VoteRecord::commitment_for(
+                &vote_record.job,
+                &vote_record.voter,
+                vote_record.round,
+                bracket,
+                &salt,
+            ) == vote_record.commitment,
+            ErrorCode::InvalidReveal
         );
+        require!((bracket as usize) < RULING_BRACKETS, ErrorCode::InvalidRulingBracket);
+        
+        let index = bracket as usize;
+        dispute.votes[index] = dispute.votes[index].checked_add(1).unwrap();
+        dispute.stakes[index] = dispute.stakes[index].checked_add(vote_record.stake).unwrap();
+        vote_record.revealed = true;
+        vote_record.bracket = bracket;
+        
+        emit!(VoteRevealed {
+            job_id: job.job_id,
+            voter: vote_record.voter,
+            bracket,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
     
+    /// Appeals the first ruling during the appeal window. The side awarded
+    /// at most half (either side on an even split) posts a bond of
+    /// `appeal_bond_bps` of the escrow, and a second jury round opens with
+    /// the same timing as the first. Escrow stays locked until it ends.
+    pub fn appeal_dispute(
+        ctx: Context<AppealDispute>,
+        _job_id: u64,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let job_key = job.key();
+        let mint = job.mint;
+        let appellant = ctx.accounts.appellant.key();
+        let config = &ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
+        require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        require!(dispute.appeal.is_none(), ErrorCode::AlreadyAppealed);
+        // An extended round only has its new voting window once its panel is drawn
+        require!(dispute.panel_selected, ErrorCode::PanelNotSelected);
+        require!(
+            now >= dispute.voting_end && now < dispute.voting_end + config.appeal_period,
+            ErrorCode::AppealWindowClosed
+        );
+        require!(
+            !dispute.needs_extension(config.quorum, config.max_voting_extensions),
+            ErrorCode::QuorumNotReached
+        );
+        
+        // Only the side that lost the first ruling can appeal it
+        let ruling = dispute.tally(config.quorum);
+        let raiser_share = ruling.raiser_share.unwrap_or(config.fallback_raiser_share);
+        if appellant == dispute.raiser {
+            require!(raiser_share <= 50, ErrorCode::NotLosingSide);
+        } else if appellant == dispute.against {
+            require!(raiser_share >= 50, ErrorCode::NotLosingSide);
+        } else {
+            return err!(ErrorCode::UnauthorizedUser);
+        }
+        
+        let escrow = Escrow::job(
+            job_key,
+            mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let bond = ((escrow.balance()? as u128) * (config.appeal_bond_bps as u128) / 10_000) as u64;
+        escrow.deposit(
+            &ctx.accounts.appellant.to_account_info(),
+            ctx.accounts.appellant_token.as_ref(),
+            bond,
+        )?;
+        
+        // Settle the first round and open the second one
+        let voting_period = dispute.voting_end - dispute.voting_start;
+        let reveal_period = dispute.voting_end - dispute.commit_end;
+        let appealed_share = ruling.raiser_share;
+        dispute.rulings.push(ruling);
+        dispute.appeal = Some(Appeal {
+            appellant,
+            bond,
+            created_at: now,
+        });
+        dispute.round = 2;
+        dispute.extensions = 0;
+        dispute.selection_slot = Clock::get()?.slot + SELECTION_DELAY_SLOTS;
+        dispute.panel_selected = false;
+        dispute.prior_jurors.extend(dispute.panel.drain(..).map(|seat| seat.juror));
+        dispute.voting_start = now;
+        dispute.commit_end = now + voting_period - reveal_period;
+        dispute.voting_end = now + voting_period;
+        dispute.commit_count = 0;
+        dispute.votes = [0; RULING_BRACKETS];
+        dispute.stake_committed = 0;
+        dispute.stakes = [0; RULING_BRACKETS];
+        job.updated_at = now;
+        
+        let dispute = job.dispute.as_ref().unwrap();
+        emit!(DisputeAppealed {
+            job_id: job.job_id,
+            appellant,
+            bond,
+            raiser_share: appealed_share,
+            commit_end: dispute.commit_end,
+            voting_end: dispute.voting_end,
+            timestamp: now,
+        });
+        
         Ok(())
     }
     
     // ============================================================================
     // CORRECTED FINALIZE_DISPUTE FUNCTION
     // ============================================================================
+    /// Pays out the dispute once voting and the appeal window are over.
+    ///
+    /// A round short of its quorum of commits is only extended by calling
+    /// this after `commit_end`, up to `max_voting_extensions` times. Reveals
+    /// stay closed until someone does, so either party or a juror has to
+    /// call it to move the dispute on.
         pub fn finalize_dispute(
         ctx: Context<FinalizeDispute>,
         _job_id: u64,
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_FUNDS)?;
         let job = &mut ctx.accounts.job;
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
+        let config = &ctx.accounts.config;
+        let job_id = job.job_id;
     
         // FIX 1: Validate dispute exists and is in correct state
-        let dispute = job.dispute.as_ref().ok_or(ErrorCode::NoDispute)?;
+        let dispute = job.dispute.as_mut().ok_or(ErrorCode::NoDispute)?;
         require!(dispute.status == DisputeStatus::Open, ErrorCode::DisputeNotOpen);
+        require!(dispute.panel_selected, ErrorCode::PanelNotSelected);
+        require!(now >= dispute.commit_end, ErrorCode::VotingStillActive);
+        
+        // A round short of its quorum of commits is extended instead of
+        // settled, before any vote was revealed. Panel members who did not
+        // vote are suspended and their seats are redrawn by select_jurors
+        // from a new selection slot.
+        let round = dispute.round;
+        let quorum = config.quorum_for(round);
+        if dispute.needs_extension(quorum, config.max_voting_extensions) {
+            let registry = &mut ctx.accounts.juror_registry;
+            let absent = dispute.remove_absent_jurors();
+            registry.suspend(&absent, now + config.juror_suspension_period);
+            dispute.extend_voting(clock.slot);
+            
+            if !absent.is_empty() {
+                emit!(AbsentJurorsRemoved {
+                    job_id,
+                    round,
+                    jurors: absent,
+                    timestamp: now,
+                });
+            }
+            emit!(VotingExtended {
+                job_id,
+                round,
+                extensions: dispute.extensions,
+                commit_count: dispute.commit_count,
+                selection_slot: dispute.selection_slot,
+                timestamp: now,
+            });
+            job.updated_at = now;
+            return Ok(());
+        }
+        let dispute = job.dispute.as_ref().unwrap();
         require!(now >= dispute.voting_end, ErrorCode::VotingStillActive);
+        
+        // The first ruling can be appealed until the appeal window closes
+        require!(
+            dispute.round > 1 || now >= dispute.voting_end + config.appeal_period,
+            ErrorCode::AppealWindowOpen
+        );
     
         // FIX 2: Verify the correct accounts are passed
         require!(
@@ -752,210 +2636,725 @@ pub mod backend {
             ErrorCode::InvalidAgainstAccount
         );
     
-        let escrow_lamports = ctx.accounts.escrow.lamports();
-        let votes_for = dispute.votes_for_raiser;
-        let votes_against = dispute.votes_for_against;
-    
-        let job_key = job.key();
-        let bump = ctx.bumps.escrow;
-        let escrow_seeds: &[&[u8]] = &[
-            b"escrow",
-            job_key.as_ref(),
-            &[bump],
-        ];
-        let signer = &[escrow_seeds];
-    
-        // FIX 3: Handle the case where there are no votes
-        if votes_for == 0 && votes_against == 0 {
-            // No votes cast - treat as a tie
-            let half = escrow_lamports / 2;
-            let rem = escrow_lamports - half;
-    
-            if half > 0 {
-                let ix1 = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.raiser,
-                    half,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix1,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.raiser.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
-            }
-    
-            if rem > 0 {
-                let ix2 = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.against,
-                    rem,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix2,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.against.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
-            }
-    
-            ctx.accounts.raiser_user.reputation = ctx.accounts.raiser_user.reputation.checked_add(5).unwrap();
-            ctx.accounts.against_user.reputation = ctx.accounts.against_user.reputation.checked_add(5).unwrap();
-            ctx.accounts.raiser_user.total_earnings = ctx.accounts.raiser_user.total_earnings.checked_add(half).unwrap();
-            ctx.accounts.against_user.total_earnings = ctx.accounts.against_user.total_earnings.checked_add(rem).unwrap();
-    
-            job.total_paid = escrow_lamports;
-        } else if votes_for > votes_against {
-            // Raiser wins
-            if escrow_lamports > 0 {
-                let ix = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.raiser,
-                    escrow_lamports,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.raiser.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
-            }
-    
-            // FIX 4: Use checked arithmetic for reputation changes
-            ctx.accounts.raiser_user.reputation = ctx.accounts.raiser_user.reputation.checked_add(20).unwrap();
-            ctx.accounts.against_user.reputation = ctx.accounts.against_user.reputation.saturating_sub(20); // Use saturating_sub to prevent underflow
-            ctx.accounts.raiser_user.completed_jobs = ctx.accounts.raiser_user.completed_jobs.checked_add(1).unwrap();
-            ctx.accounts.raiser_user.total_earnings = ctx.accounts.raiser_user.total_earnings.checked_add(escrow_lamports).unwrap();
-    
-            // FIX 5: Update pending jobs counter based on role
-            if dispute.raiser_role == DisputeRole::Freelancer {
-                ctx.accounts.raiser_user.pending_jobs = ctx.accounts.raiser_user.pending_jobs.saturating_sub(1);
-            }
-            if dispute.against_role == DisputeRole::Freelancer {
-                ctx.accounts.against_user.pending_jobs = ctx.accounts.against_user.pending_jobs.saturating_sub(1);
-            }
-    
-            job.total_paid = escrow_lamports;
-        } else if votes_for < votes_against {
-            // Against wins
-            if escrow_lamports > 0 {
-                let ix = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.against,
-                    escrow_lamports,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.against.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
-            }
-    
-            ctx.accounts.against_user.reputation = ctx.accounts.against_user.reputation.checked_add(20).unwrap();
-            ctx.accounts.raiser_user.reputation = ctx.accounts.raiser_user.reputation.saturating_sub(20);
-            ctx.accounts.against_user.completed_jobs = ctx.accounts.against_user.completed_jobs.checked_add(1).unwrap();
-            ctx.accounts.against_user.total_earnings = ctx.accounts.against_user.total_earnings.checked_add(escrow_lamports).unwrap();
+        // Without a quorum an appeal leaves the first ruling standing, and a
+        // dispute that never reached one pays out the fallback share
+        let ruling = dispute.tally(quorum);
+        let first_share = dispute.rulings.first().and_then(|first| first.raiser_share);
+        let (ruling_round, raiser_share) = match (ruling.raiser_share, first_share) {
+            (Some(share), _) => (round, share),
+            (None, Some(share)) => (1, share),
+            (None, None) => (round, config.fallback_raiser_share),
+        };
+        let votes_revealed = ruling.votes_revealed;
+        let raiser_role = dispute.raiser_role.clone();
+        let raiser = dispute.raiser;
+        let against = dispute.against;
+        let stake_mint = dispute.stake_mint;
+        let filing_fee = dispute.filing_fee;
+        
+        // The bond is returned only if the appeal improved the appellant's
+        // share. An appeal that reached no ruling leaves the first ruling
+        // standing and forfeits the bond to the other party like a lost one.
+        let bond_release = dispute.appeal.as_ref().map(|appeal| {
+            let appealed_share = first_share.unwrap_or(config.fallback_raiser_share);
+            let (recipient, refunded) =
+                appeal.bond_recipient(raiser, against, appealed_share, raiser_share);
+            (appeal.appellant, recipient, appeal.bond, refunded)
+        });
     
-            if dispute.raiser_role == DisputeRole::Freelancer {
-                ctx.accounts.raiser_user.pending_jobs = ctx.accounts.raiser_user.pending_jobs.saturating_sub(1);
-            }
-            if dispute.against_role == DisputeRole::Freelancer {
-                ctx.accounts.against_user.pending_jobs = ctx.accounts.against_user.pending_jobs.saturating_sub(1);
-            }
+        let escrow = Escrow::job(
+            job.key(),
+            job.mint,
+            &ctx.accounts.escrow,
+            ctx.bumps.escrow,
+            ctx.accounts.escrow_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            &ctx.accounts.system_program,
+        );
+        let bond = bond_release.map_or(0, |(_, _, bond, _)| bond);
+        let escrow_balance = escrow.balance()?.checked_sub(bond).unwrap();
+        let raiser_amount = ((escrow_balance as u128) * (raiser_share as u128) / 100) as u64;
+        let against_amount = escrow_balance - raiser_amount;
     
-            job.total_paid = escrow_lamports;
+        // The platform fee only applies to the freelancer's share
+        let (raiser_fee, against_fee) = if raiser_role == DisputeRole::Freelancer {
+            (config.fee_for(raiser_amount), 0)
         } else {
-            // Tie → split escrow 50–50
-            let half = escrow_lamports / 2;
-            let rem = escrow_lamports - half;
-    
-            if half > 0 {
-                let ix1 = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.raiser,
-                    half,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix1,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.raiser.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
-            }
-    
-            if rem > 0 {
-                let ix2 = system_instruction::transfer(
-                    &ctx.accounts.escrow.key(),
-                    &dispute.against,
-                    rem,
-                );
-                anchor_lang::solana_program::program::invoke_signed(
-                    &ix2,
-                    &[
-                        ctx.accounts.escrow.to_account_info(),
-                        ctx.accounts.against.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                    signer,
-                )?;
+            (0, config.fee_for(against_amount))
+        };
+        escrow.release_with_fee(
+            &ctx.accounts.raiser,
+            ctx.accounts.raiser_token.as_ref(),
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            raiser_amount,
+            raiser_fee,
+        )?;
+        escrow.release_with_fee(
+            &ctx.accounts.against,
+            ctx.accounts.against_token.as_ref(),
+            &ctx.accounts.treasury,
+            ctx.accounts.treasury_token.as_ref(),
+            against_amount,
+            against_fee,
+        )?;
+        if let Some((_, recipient, bond, _)) = bond_release {
+            if recipient == raiser {
+                escrow.release(&ctx.accounts.raiser, ctx.accounts.raiser_token.as_ref(), bond)?;
+            } else {
+                escrow.release(&ctx.accounts.against, ctx.accounts.against_token.as_ref(), bond)?;
             }
+        }
+        
+        // A raiser who wins under the standing ruling, or whose dispute no
+        // round ruled on, gets the filing fee back. Otherwise it goes to the
+        // reward pool of the round whose ruling stands.
+        let ruled = ruling.raiser_share.is_some() || first_share.is_some();
+        let filing_fee_refunded = raiser_share > 50 || !ruled;
+        if filing_fee_refunded {
+            let vault = Escrow::dispute_vault(
+                job.key(),
+                stake_mint,
+                &ctx.accounts.dispute_vault,
+                ctx.bumps.dispute_vault,
+                ctx.accounts.dispute_vault_token.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+                &ctx.accounts.system_program,
+            );
+            vault.release(&ctx.accounts.raiser, ctx.accounts.raiser_stake_token.as_ref(), filing_fee)?;
+        }
     
-            ctx.accounts.raiser_user.reputation = ctx.accounts.raiser_user.reputation.checked_add(10).unwrap();
-            ctx.accounts.against_user.reputation = ctx.accounts.against_user.reputation.checked_add(10).unwrap();
-            ctx.accounts.raiser_user.completed_jobs = ctx.accounts.raiser_user.completed_jobs.checked_add(1).unwrap();
-            ctx.accounts.against_user.completed_jobs = ctx.accounts.against_user.completed_jobs.checked_add(1).unwrap();
-            ctx.accounts.raiser_user.total_earnings = ctx.accounts.raiser_user.total_earnings.checked_add(half).unwrap();
-            ctx.accounts.against_user.total_earnings = ctx.accounts.against_user.total_earnings.checked_add(rem).unwrap();
+        // Each side's dispute record moves by the share it was awarded
+        let raiser_user = &mut ctx.accounts.raiser_user;
+        let against_user = &mut ctx.accounts.against_user;
+        raiser_user.touch(now, config.reputation_half_life);
+        against_user.touch(now, config.reputation_half_life);
+        raiser_user.total_earnings = raiser_user.total_earnings.checked_add(raiser_amount).unwrap();
+        against_user.total_earnings = against_user.total_earnings.checked_add(against_amount).unwrap();
+        raiser_user.record_dispute(raiser_share);
+        against_user.record_dispute(100 - raiser_share);
     
-            if dispute.raiser_role == DisputeRole::Freelancer {
-                ctx.accounts.raiser_user.pending_jobs = ctx.accounts.raiser_user.pending_jobs.saturating_sub(1);
+        // The job is over for both sides whatever the outcome, but only a
+        // ruling counts it as completed for the side awarded at least half
+        raiser_user.pending_jobs = raiser_user.pending_jobs.saturating_sub(1);
+        against_user.pending_jobs = against_user.pending_jobs.saturating_sub(1);
+        if ruled {
+            if raiser_share >= 50 {
+                raiser_user.completed_jobs = raiser_user.completed_jobs.checked_add(1).unwrap();
             }
-            if dispute.against_role == DisputeRole::Freelancer {
-                ctx.accounts.against_user.pending_jobs = ctx.accounts.against_user.pending_jobs.saturating_sub(1);
+            if raiser_share <= 50 {
+                against_user.completed_jobs = against_user.completed_jobs.checked_add(1).unwrap();
             }
-    
-            job.total_paid = escrow_lamports;
         }
     
+        job.total_paid = job.total_paid.checked_add(escrow_balance).unwrap();
+    
         // Final state updates
         let job = &mut ctx.accounts.job;
         let mut dispute = job.dispute.take().unwrap();
         dispute.status = DisputeStatus::Resolved;
         dispute.resolved_at = Some(now);
-        dispute.resolution = Some(format!(
-            "Votes: {} for raiser, {} for against",
-            votes_for, votes_against
-        ));
+        dispute.rulings.push(ruling);
+        if !filing_fee_refunded {
+            let standing = &mut dispute.rulings[ruling_round as usize - 1];
+            standing.reward_pool = standing.reward_pool.checked_add(filing_fee).unwrap();
+            standing.unclaimed_amount = standing.unclaimed_amount.checked_add(filing_fee).unwrap();
+        }
+        let absent = dispute.remove_absent_jurors();
+        ctx.accounts
+            .juror_registry
+            .suspend(&absent, now + config.juror_suspension_period);
+        dispute.resolution = Some(DisputeResolution {
+            raiser_share,
+            raiser_amount,
+            against_amount,
+            filing_fee_refunded,
+        });
         
         job.dispute = Some(dispute);
         job.status = JobStatus::Completed;
         job.escrow = Pubkey::default();
         job.updated_at = now;
     
-        msg!(
-            "Dispute finalized for job {}. Votes: {} for raiser, {} for against",
-            job.job_id,
-            votes_for,
-            votes_against
-        );
+        emit!(DisputeFinalized {
+            job_id: job.job_id,
+            raiser,
+            against,
+            round: ruling_round,
+            raiser_share,
+            votes_revealed,
+            raiser_amount,
+            against_amount,
+            filing_fee_refunded,
+            timestamp: now,
+        });
+        for (recipient, amount, fee) in [
+            (raiser, raiser_amount, raiser_fee),
+            (against, against_amount, against_fee),
+        ] {
+            if amount > 0 {
+                emit!(EscrowReleased {
+                    job_id: job.job_id,
+                    recipient,
+                    amount,
+                    fee,
+                    timestamp: now,
+                });
+            }
+        }
+        if !absent.is_empty() {
+            emit!(AbsentJurorsRemoved {
+                job_id: job.job_id,
+                round,
+                jurors: absent,
+                timestamp: now,
+            });
+        }
+        if let Some((appellant, recipient, bond, refunded)) = bond_release {
+            emit!(AppealBondReleased {
+                job_id: job.job_id,
+                appellant,
+                recipient,
+                bond,
+                refunded,
+                timestamp: now,
+            });
+        }
     
         Ok(())
     }
     
 }
+
+/// Randomness for drawing a jury panel: the hash of the first block at or
+/// after `selection_slot`, hashed with the job key. Fails until that block
+/// exists, and is `None` once it has left the SlotHashes window.
+fn panel_entropy(
+    slot_hashes: &AccountInfo,
+    job: &Pubkey,
+    selection_slot: u64,
+) -> Result<Option<[u8; 32]>> {
+    // Raw sysvar layout: u64 entry count, then (slot, hash) pairs, newest first
+    let data = slot_hashes.try_borrow_data()?;
+    require!(data.len() >= 8, ErrorCode::SelectionTooEarly);
+    let count = (u64::from_le_bytes(data[..8].try_into().unwrap()) as usize)
+        .min((data.len() - 8) / 40);
+    let entry = |index: usize| {
+        let offset = 8 + index * 40;
+        let slot = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        (slot, &data[offset + 8..offset + 40])
+    };
+    require!(
+        count > 0 && entry(0).0 >= selection_slot,
+        ErrorCode::SelectionTooEarly
+    );
+    // Walk back to the oldest entry at or after the selection slot. It is
+    // the first such block unless older entries have left the window.
+    let mut first = entry(0);
+    let mut window_covers_slot = first.0 == selection_slot;
+    for index in 1..count {
+        let (slot, slot_hash) = entry(index);
+        if slot < selection_slot {
+            window_covers_slot = true;
+            break;
+        }
+        first = (slot, slot_hash);
+        window_covers_slot = slot == selection_slot;
+    }
+    if !window_covers_slot {
+        return Ok(None);
+    }
+    Ok(Some(
+        Sha256::new()
+            .chain_update(first.1)
+            .chain_update(job)
+            .finalize()
+            .into(),
+    ))
+}
+
+/// Keeps admin supplied settings within sane bounds. `rent_minimum` is the
+/// rent-exempt minimum of a lamport vault, which SOL fees and stakes cover.
+fn validate_config(config: &Config, rent_minimum: u64) -> Result<()> {
+    require!(
+        config.platform_fee_bps <= MAX_PLATFORM_FEE_BPS,
+        ErrorCode::InvalidConfig
+    );
+    // Caps the number of jurors per dispute
+    require!(
+        config.max_voters > 0 && config.max_voters <= MAX_VOTERS as u64,
+        ErrorCode::InvalidConfig
+    );
+    require!(
+        config.max_voting_period > 0
+            && config.max_offer_duration > 0
+            && config.deadline_grace_period >= 0
+            && config.review_period > 0
+            && config.reputation_half_life > 0
+            && config.juror_min_account_age >= 0
+            && config.reveal_period > 0
+            && config.reveal_period < config.max_voting_period
+            && config.appeal_period > 0
+            && config.stake_claim_period > 0
+            && config.juror_suspension_period > 0,
+        ErrorCode::InvalidConfig
+    );
+    require!(
+        config.juror_min_reputation <= MAX_REPUTATION && config.juror_stake > 0,
+        ErrorCode::InvalidConfig
+    );
+    // The first SOL stake may open the dispute vault, and what remains after
+    // partial claims has to stay rent exempt
+    require!(
+        config.stake_mint.is_some() || config.juror_stake >= rent_minimum,
+        ErrorCode::InvalidConfig
+    );
+    require!(
+        config.quorum > 0
+            && config.quorum <= config.max_voters
+            && config.fallback_raiser_share <= 100,
+        ErrorCode::InvalidConfig
+    );
+    // Appeals need a larger jury that is at least as experienced
+    require!(
+        config.appeal_bond_bps <= 10_000
+            && config.appeal_quorum >= config.quorum
+            && config.appeal_quorum <= config.max_voters
+            && config.appeal_juror_min_completed_jobs >= config.juror_min_completed_jobs
+            && config.appeal_juror_min_reputation >= config.juror_min_reputation
+            && config.appeal_juror_min_reputation <= MAX_REPUTATION,
+        ErrorCode::InvalidConfig
+    );
+    // Panels must be able to reach their round's quorum
+    require!(
+        config.panel_size >= config.quorum
+            && config.appeal_panel_size >= config.appeal_quorum
+            && config.appeal_panel_size >= config.panel_size
+            && config.appeal_panel_size <= config.max_voters.min(MAX_PANEL_SIZE as u64),
+        ErrorCode::InvalidConfig
+    );
+    // A SOL filing fee opens the dispute vault, which has to be rent exempt
+    require!(
+        config.stake_mint.is_some()
+            || config.dispute_fee == 0
+            || config.dispute_fee >= rent_minimum,
+        ErrorCode::InvalidConfig
+    );
+    Ok(())
+}
+
+/// Validates the milestone plan for a job and turns it into pending milestones.
+fn build_milestones(
+    inputs: Vec<MilestoneInput>,
+    budget: u64,
+    deadline: i64,
+    now: i64,
+    min_tail: u64,
+) -> Result<Vec<Milestone>> {
+    require!(
+        inputs.len() <= MAX_MILESTONES,
+        ErrorCode::TooManyMilestones
+    );
+    if inputs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut total: u64 = 0;
+    let mut previous_due = now;
+    let mut milestones = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        require!(input.title.len() <= 50, ErrorCode::TitleTooLong);
+        require!(input.amount > 0, ErrorCode::InvalidMilestoneAmount);
+        // Due dates must be in the future and in delivery order
+        require!(
+            input.due_date > previous_due,
+            ErrorCode::InvalidMilestoneDate
+        );
+        require!(
+            input.due_date <= deadline,
+            ErrorCode::MilestoneAfterDeadline
+        );
+        total = total
+            .checked_add(input.amount)
+            .ok_or(ErrorCode::MilestoneAmountMismatch)?;
+        previous_due = input.due_date;
+
+        milestones.push(Milestone {
+            title: input.title,
+            amount: input.amount,
+            due_date: input.due_date,
+            status: MilestoneStatus::Pending,
+            submitted_at: None,
+            approved_at: None,
+        });
+    }
+    require!(total == budget, ErrorCode::MilestoneAmountMismatch);
+    require_milestone_tail(&milestones, min_tail)?;
+
+    Ok(milestones)
+}
+
+/// Scales milestone amounts proportionally to a new total. Rounding dust goes
+/// to the last milestone so the amounts always add up to `new_total`.
+fn rescale_milestones(
+    milestones: &mut [Milestone],
+    old_total: u64,
+    new_total: u64,
+    min_tail: u64,
+) -> Result<()> {
+    if milestones.is_empty() || old_total == 0 {
+        return Ok(());
+    }
+    let mut assigned: u64 = 0;
+    let last = milestones.len() - 1;
+    for (i, milestone) in milestones.iter_mut().enumerate() {
+        if i == last {
+            milestone.amount = new_total - assigned;
+        } else {
+            milestone.amount =
+                ((milestone.amount as u128) * (new_total as u128) / (old_total as u128)) as u64;
+            assigned += milestone.amount;
+        }
+    }
+    require_milestone_tail(milestones, min_tail)
+}
+
+/// Smallest balance a job escrow may keep between milestone releases. A
+/// lamport escrow cannot be left below the rent-exempt minimum unless it is
+/// emptied, token escrows have no such limit.
+fn milestone_tail_minimum(mint: Option<Pubkey>) -> Result<u64> {
+    match mint {
+        None => Ok(Rent::get()?.minimum_balance(0)),
+        Some(_) => Ok(0),
+    }
+}
+
+/// Checks that every release but the last leaves at least `min_tail` in
+/// escrow. The smallest such remainder is the last milestone on its own.
+fn require_milestone_tail(milestones: &[Milestone], min_tail: u64) -> Result<()> {
+    if let [_, .., last] = milestones {
+        require!(last.amount >= min_tail, ErrorCode::MilestoneBelowRent);
+    }
+    Ok(())
+}
+
+/// Index of the first milestone that has not been approved yet.
+fn current_milestone(milestones: &[Milestone]) -> Option<usize> {
+    milestones
+        .iter()
+        .position(|m| m.status != MilestoneStatus::Approved)
+}
+
+/// Checks the editable fields of a job, `None` leaves a field unchecked.
+/// Shared by `create_job` and `update_job` so their limits stay the same.
+fn validate_job_fields(
+    title: Option<&str>,
+    description: Option<&str>,
+    deadline: Option<i64>,
+    skills: Option<&[String]>,
+    category: Option<&str>,
+    now: i64,
+) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(deadline > now, ErrorCode::InvalidDeadline);
+    }
+    if let Some(title) = title {
+        require!(title.len() <= 100, ErrorCode::TitleTooLong);
+    }
+    if let Some(description) = description {
+        require!(description.len() <= 1000, ErrorCode::DescriptionTooLong);
+    }
+    if let Some(skills) = skills {
+        require!(skills.len() <= 10, ErrorCode::TooManySkills);
+    }
+    if let Some(category) = category {
+        require!(category.len() <= 50, ErrorCode::CategoryTooLong);
+    }
+    Ok(())
+}
+
+/// Checks the cover letter, delivery estimate and screening answers of a bid.
+fn validate_proposal_details(
+    job: &Job,
+    cover_letter: &str,
+    estimated_delivery: i64,
+    screening_answers: &[String],
+    now: i64,
+) -> Result<()> {
+    require!(cover_letter.len() <= 1000, ErrorCode::CoverLetterTooLong);
+    require!(
+        estimated_delivery > now && estimated_delivery <= job.deadline,
+        ErrorCode::InvalidDeliveryDate
+    );
+    require!(
+        screening_answers.len() == job.screening_questions.len(),
+        ErrorCode::ScreeningAnswersMismatch
+    );
+    require!(
+        screening_answers.iter().all(|answer| answer.len() <= 300),
+        ErrorCode::ScreeningAnswerTooLong
+    );
+    Ok(())
+}
+
+/// Puts a job whose offer fell through back on the market.
+fn reopen_job(job: &mut Job, now: i64) {
+    job.freelancer = None;
+    job.status = JobStatus::Open;
+    job.offered_amount = 0;
+    job.offer_expires_at = None;
+    job.escrow = Pubkey::default();
+    job.updated_at = now;
+}
+
+/// Fails if `account` is not the collaboration PDA of the two users, or if
+/// it exists, i.e. the users have worked together.
+fn require_no_collaboration(account: &AccountInfo, user: &Pubkey, other: &Pubkey) -> Result<()> {
+    require!(!has_collaborated(account, user, other)?, ErrorCode::JurorWorkedWithParty);
+    Ok(())
+}
+
+/// Whether `account`, which must be the Collaboration PDA of the two users,
+/// exists.
+fn has_collaborated(account: &AccountInfo, user: &Pubkey, other: &Pubkey) -> Result<bool> {
+    let [first, second] = Collaboration::pair(user, other);
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"collab", first.as_ref(), second.as_ref()], &crate::ID);
+    require_keys_eq!(account.key(), expected, ErrorCode::InvalidCollaborationAccount);
+    Ok(!account.data_is_empty())
+}
+
+/// Whether the freelancer's final delivery came in before the job deadline.
+fn delivered_on_time(job: &Job) -> bool {
+    job.work_submitted_at
+        .is_some_and(|submitted_at| submitted_at <= job.deadline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw SlotHashes data for `(slot, hash byte)` entries, newest first.
+    fn slot_hashes(entries: &[(u64, u8)]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for &(slot, byte) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[byte; 32]);
+        }
+        data
+    }
+
+    fn entropy_at(entries: &[(u64, u8)], selection_slot: u64) -> Result<Option<[u8; 32]>> {
+        let key = Pubkey::new_unique();
+        let (mut lamports, mut data) = (0, slot_hashes(entries));
+        let account = AccountInfo::new(
+            &key, false, false, &mut lamports, &mut data, &key, false, 0,
+        );
+        panel_entropy(&account, &Pubkey::default(), selection_slot)
+    }
+
+    fn expected_entropy(byte: u8) -> Option<[u8; 32]> {
+        Some(
+            Sha256::new()
+                .chain_update([byte; 32])
+                .chain_update(Pubkey::default())
+                .finalize()
+                .into(),
+        )
+    }
+
+    #[test]
+    fn panel_entropy_uses_the_selection_slot_hash() {
+        let entries = [(105, 5), (104, 4), (103, 3)];
+        assert_eq!(entropy_at(&entries, 104).unwrap(), expected_entropy(4));
+        assert_eq!(entropy_at(&entries, 105).unwrap(), expected_entropy(5));
+    }
+
+    #[test]
+    fn panel_entropy_takes_the_first_block_after_a_skipped_slot() {
+        let entries = [(110, 10), (107, 7), (103, 3)];
+        assert_eq!(entropy_at(&entries, 105).unwrap(), expected_entropy(7));
+    }
+
+    #[test]
+    fn panel_entropy_fails_before_the_selection_slot() {
+        assert!(entropy_at(&[(103, 3), (102, 2)], 104).is_err());
+        assert!(entropy_at(&[], 104).is_err());
+    }
+
+    #[test]
+    fn panel_entropy_is_none_once_the_slot_left_the_window() {
+        assert_eq!(entropy_at(&[(110, 10), (109, 9)], 105).unwrap(), None);
+        assert_eq!(entropy_at(&[(110, 10), (109, 9)], 109).unwrap(), expected_entropy(9));
+    }
+
+    fn inputs(amounts: &[u64]) -> Vec<MilestoneInput> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, &amount)| MilestoneInput {
+                title: format!("Milestone {i}"),
+                amount,
+                due_date: 100 * (i as i64 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build_milestones_requires_the_budget_and_order() {
+        let milestones = build_milestones(inputs(&[30, 70]), 100, 200, 0, 0).unwrap();
+        assert_eq!(milestones.len(), 2);
+        assert!(milestones.iter().all(|m| m.status == MilestoneStatus::Pending));
+        assert_eq!(
+            build_milestones(inputs(&[30, 60]), 100, 200, 0, 0).err(),
+            Some(ErrorCode::MilestoneAmountMismatch.into())
+        );
+        assert_eq!(
+            build_milestones(inputs(&[30, 70]), 100, 150, 0, 0).err(),
+            Some(ErrorCode::MilestoneAfterDeadline.into())
+        );
+        assert_eq!(
+            build_milestones(inputs(&[30, 70]), 100, 200, 100, 0).err(),
+            Some(ErrorCode::InvalidMilestoneDate.into())
+        );
+        assert_eq!(
+            build_milestones(inputs(&[0, 100]), 100, 200, 0, 0).err(),
+            Some(ErrorCode::InvalidMilestoneAmount.into())
+        );
+        assert_eq!(
+            build_milestones(inputs(&[1; MAX_MILESTONES + 1]), 6, 1_000, 0, 0).err(),
+            Some(ErrorCode::TooManyMilestones.into())
+        );
+    }
+
+    #[test]
+    fn build_milestones_keeps_the_escrow_above_the_tail_minimum() {
+        assert_eq!(
+            build_milestones(inputs(&[90, 10]), 100, 200, 0, 20).err(),
+            Some(ErrorCode::MilestoneBelowRent.into())
+        );
+        assert!(build_milestones(inputs(&[80, 20]), 100, 200, 0, 20).is_ok());
+        // A single milestone empties the escrow in one release
+        assert!(build_milestones(inputs(&[10]), 10, 200, 0, 20).is_ok());
+    }
+
+    #[test]
+    fn rescale_milestones_gives_the_rounding_dust_to_the_last() {
+        let mut milestones = build_milestones(inputs(&[1, 1, 1]), 3, 300, 0, 0).unwrap();
+        rescale_milestones(&mut milestones, 3, 100, 0).unwrap();
+        let amounts: Vec<u64> = milestones.iter().map(|m| m.amount).collect();
+        assert_eq!(amounts, vec![33, 33, 34]);
+        assert_eq!(
+            rescale_milestones(&mut milestones, 100, 50, 20).err(),
+            Some(ErrorCode::MilestoneBelowRent.into())
+        );
+    }
+
+    /// The config `initialize_config` creates.
+    fn default_config() -> Config {
+        Config {
+            admin: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            platform_fee_bps: 250,
+            max_voters: MAX_VOTERS as u64,
+            max_voting_period: DEFAULT_MAX_VOTING_PERIOD,
+            max_offer_duration: DEFAULT_MAX_OFFER_DURATION,
+            deadline_grace_period: DEFAULT_DEADLINE_GRACE_PERIOD,
+            review_period: DEFAULT_REVIEW_PERIOD,
+            reputation_half_life: DEFAULT_REPUTATION_HALF_LIFE,
+            juror_min_account_age: DEFAULT_JUROR_MIN_ACCOUNT_AGE,
+            juror_min_completed_jobs: DEFAULT_JUROR_MIN_COMPLETED_JOBS,
+            juror_min_reputation: DEFAULT_JUROR_MIN_REPUTATION,
+            stake_mint: None,
+            juror_stake: DEFAULT_JUROR_STAKE,
+            reveal_period: DEFAULT_REVEAL_PERIOD,
+            quorum: DEFAULT_QUORUM,
+            max_voting_extensions: DEFAULT_MAX_VOTING_EXTENSIONS,
+            fallback_raiser_share: DEFAULT_FALLBACK_RAISER_SHARE,
+            appeal_period: DEFAULT_APPEAL_PERIOD,
+            appeal_bond_bps: DEFAULT_APPEAL_BOND_BPS,
+            appeal_quorum: DEFAULT_APPEAL_QUORUM,
+            appeal_juror_min_completed_jobs: DEFAULT_APPEAL_JUROR_MIN_COMPLETED_JOBS,
+            appeal_juror_min_reputation: DEFAULT_APPEAL_JUROR_MIN_REPUTATION,
+            panel_size: DEFAULT_PANEL_SIZE,
+            appeal_panel_size: DEFAULT_APPEAL_PANEL_SIZE,
+            dispute_fee: DEFAULT_DISPUTE_FEE,
+            stake_claim_period: DEFAULT_STAKE_CLAIM_PERIOD,
+            juror_suspension_period: DEFAULT_JUROR_SUSPENSION_PERIOD,
+            paused: 0,
+            bump: 255,
+        }
+    }
+
+    /// Whether `validate_config` accepts the default config after `change`.
+    fn config_valid(change: impl FnOnce(&mut Config)) -> bool {
+        let mut config = default_config();
+        change(&mut config);
+        validate_config(&config, Rent::default().minimum_balance(0)).is_ok()
+    }
+
+    #[test]
+    fn validate_config_accepts_the_defaults() {
+        assert!(config_valid(|_| {}));
+    }
+
+    #[test]
+    fn validate_config_bounds_the_fee_and_voters() {
+        assert!(config_valid(|c| c.platform_fee_bps = MAX_PLATFORM_FEE_BPS));
+        assert!(!config_valid(|c| c.platform_fee_bps = MAX_PLATFORM_FEE_BPS + 1));
+        assert!(!config_valid(|c| c.max_voters = 0));
+        assert!(!config_valid(|c| c.max_voters = MAX_VOTERS as u64 + 1));
+    }
+
+    #[test]
+    fn validate_config_rejects_unusable_periods() {
+        assert!(!config_valid(|c| c.review_period = 0));
+        assert!(!config_valid(|c| c.deadline_grace_period = -1));
+        assert!(!config_valid(|c| c.reveal_period = c.max_voting_period));
+        assert!(!config_valid(|c| c.stake_claim_period = 0));
+    }
+
+    #[test]
+    fn validate_config_keeps_appeals_at_least_as_strict() {
+        assert!(!config_valid(|c| c.appeal_quorum = c.quorum - 1));
+        assert!(!config_valid(|c| c.appeal_bond_bps = 10_001));
+        assert!(!config_valid(|c| c.appeal_juror_min_reputation = c.juror_min_reputation - 1));
+        assert!(!config_valid(|c| c.appeal_juror_min_completed_jobs = 0));
+    }
+
+    #[test]
+    fn validate_config_keeps_panels_able_to_reach_quorum() {
+        assert!(!config_valid(|c| c.quorum = c.panel_size + 1));
+        assert!(!config_valid(|c| c.appeal_quorum = c.appeal_panel_size + 1));
+        assert!(!config_valid(|c| c.appeal_panel_size = c.panel_size - 1));
+        assert!(!config_valid(|c| c.appeal_panel_size = MAX_PANEL_SIZE as u64 + 1));
+    }
+
+    #[test]
+    fn validate_config_requires_sol_stakes_to_cover_rent() {
+        let rent_minimum = Rent::default().minimum_balance(0);
+        assert!(config_valid(|c| c.juror_stake = rent_minimum));
+        assert!(!config_valid(|c| c.juror_stake = rent_minimum - 1));
+        assert!(config_valid(|c| {
+            c.stake_mint = Some(Pubkey::new_unique());
+            c.juror_stake = 1;
+        }));
+        assert!(!config_valid(|c| {
+            c.stake_mint = Some(Pubkey::new_unique());
+            c.juror_stake = 0;
+        }));
+    }
+
+    #[test]
+    fn validate_config_requires_a_sol_filing_fee_to_cover_rent() {
+        let rent_minimum = Rent::default().minimum_balance(0);
+        assert!(config_valid(|c| c.dispute_fee = 0));
+        assert!(config_valid(|c| c.dispute_fee = rent_minimum));
+        assert!(!config_valid(|c| c.dispute_fee = rent_minimum - 1));
+        assert!(config_valid(|c| {
+            c.stake_mint = Some(Pubkey::new_unique());
+            c.juror_stake = 1;
+            c.dispute_fee = 1;
+        }));
+    }
+}
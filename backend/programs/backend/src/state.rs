@@ -1,9 +1,72 @@
 use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+use crate::error::ErrorCode;
 pub const MAX_EDUCATION: usize = 3;
 pub const MAX_EXPERIENCE: usize = 3;
 pub const MAX_SKILLS: usize = 10;
 pub const MAX_CERTIFICATIONS: usize = 3;
 pub const MAX_PORTFOLIO: usize = 3;
+pub const MAX_MILESTONES: usize = 5;
+pub const MAX_REVIEWS: usize = 2; // one from each party
+pub const MAX_SCREENING_QUESTIONS: usize = 3;
+pub const MAX_VOTERS: usize = 100;
+// Reviews and replies are stored in the job, which is capped at 10 KiB
+pub const MAX_REVIEW_LENGTH: usize = 300;
+pub const MAX_REPLY_LENGTH: usize = 200;
+pub const MAX_EVIDENCE: usize = 10; // submissions per dispute, from both parties
+pub const MAX_EVIDENCE_PER_PARTY: u8 = (MAX_EVIDENCE / 2) as u8;
+pub const MAX_EVIDENCE_URI_LENGTH: usize = 200;
+pub const MAX_EVIDENCE_STATEMENT_LENGTH: usize = 300;
+pub const MAX_PLATFORM_FEE_BPS: u16 = 1_000; // 10%
+pub const MAX_DISPUTE_ROUNDS: usize = 2; // the first ruling and one appeal
+pub const MAX_PANEL_SIZE: usize = 15;
+pub const MAX_REGISTERED_JURORS: usize = 200;
+// Panels are drawn from the hash of a slot this far after the round opens,
+// so nobody knows the draw when they could still influence it
+pub const SELECTION_DELAY_SLOTS: u64 = 8;
+// Instruction groups that the admin can pause independently
+pub const PAUSE_JOBS: u8 = 1 << 0;
+pub const PAUSE_BIDDING: u8 = 1 << 1;
+pub const PAUSE_FUNDS: u8 = 1 << 2;
+pub const PAUSE_DISPUTES: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_JOBS | PAUSE_BIDDING | PAUSE_FUNDS | PAUSE_DISPUTES;
+// Defaults for the platform config
+pub const DEFAULT_MAX_VOTING_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const DEFAULT_MAX_OFFER_DURATION: i64 = 14 * 24 * 60 * 60; // 14 days
+pub const DEFAULT_DEADLINE_GRACE_PERIOD: i64 = 3 * 24 * 60 * 60; // 3 days
+pub const DEFAULT_REVIEW_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const DEFAULT_REPUTATION_HALF_LIFE: i64 = 180 * 24 * 60 * 60; // 180 days
+pub const DEFAULT_JUROR_MIN_ACCOUNT_AGE: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const DEFAULT_JUROR_MIN_COMPLETED_JOBS: u64 = 1;
+pub const DEFAULT_JUROR_MIN_REPUTATION: u64 = REPUTATION_BASELINE;
+pub const DEFAULT_JUROR_STAKE: u64 = 10_000_000; // 0.01 SOL
+pub const DEFAULT_REVEAL_PERIOD: i64 = 24 * 60 * 60; // 1 day
+pub const DEFAULT_QUORUM: u64 = 3;
+pub const DEFAULT_MAX_VOTING_EXTENSIONS: u8 = 2;
+pub const DEFAULT_FALLBACK_RAISER_SHARE: u8 = 50; // percent
+pub const DEFAULT_APPEAL_PERIOD: i64 = 2 * 24 * 60 * 60; // 2 days
+pub const DEFAULT_APPEAL_BOND_BPS: u16 = 1_000; // 10% of the escrow
+pub const DEFAULT_APPEAL_QUORUM: u64 = 5;
+pub const DEFAULT_APPEAL_JUROR_MIN_COMPLETED_JOBS: u64 = 5;
+pub const DEFAULT_APPEAL_JUROR_MIN_REPUTATION: u64 = 70;
+pub const DEFAULT_PANEL_SIZE: u64 = 7;
+pub const DEFAULT_APPEAL_PANEL_SIZE: u64 = 11;
+pub const DEFAULT_DISPUTE_FEE: u64 = 50_000_000; // 0.05 SOL
+pub const DEFAULT_STAKE_CLAIM_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const DEFAULT_JUROR_SUSPENSION_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+// Jurors rule on the raiser's share of escrow in brackets of RULING_STEP percent
+pub const RULING_STEP: u8 = 10;
+pub const RULING_BRACKETS: usize = 11; // 0%, 10%, ..., 100%
+// Revealed votes within this many percent of the ruling keep their stake
+pub const RULING_TOLERANCE: u8 = 10;
+// Reputation scores run from 0 to MAX_REPUTATION, new users start at the baseline
+pub const MAX_REPUTATION: u64 = 100;
+pub const REPUTATION_BASELINE: u64 = 50;
+// Every rating average starts with this many virtual 3 star reviews
+pub const RATING_PRIOR: u64 = 2;
+// Weight of one recorded outcome in the reputation history. Fixed point so
+// decay does not round the history away between touches.
+pub const HISTORY_UNIT: u64 = 1_000_000;
 #[account]
 pub struct User{
     pub authority: Pubkey,
@@ -21,9 +84,160 @@ pub struct User{
     pub active_jobs: u64,
     pub pending_jobs: u64,
     pub cancelled_jobs: u64,
+    // Star histograms (index 0 = 1 star) of reviews received per role,
+    // this and the rest of the history are weights in HISTORY_UNITs
+    pub freelancer_ratings: [u64; 5],
+    pub client_ratings: [u64; 5],
+    pub average_rating: u16, // hundredths of a star, 0 until the first review
+    pub on_time_deliveries: u64,
+    pub late_deliveries: u64, // delivered after the deadline, or not at all
+    // Share of the escrow awarded to (or against) the user in disputes
+    pub dispute_points_won: u64,
+    pub dispute_points_lost: u64,
+    // Scores out of MAX_REPUTATION, `reputation` combines the user's roles
+    pub freelancer_score: u64,
+    pub client_score: u64,
+    pub last_activity_at: i64,
 }
 impl User {
-    pub const LEN: usize = 32 + 4 + 100 + 1 + 1 + 8 + 8 + 8 + 8 + (1 + Resume::LEN) + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+    pub const LEN: usize = 32 + 4 + 100 + 1 + 1 + 8 + 8 + 8 + 8 + (1 + Resume::LEN) + 8 + 8 + 8 + 8 + 8 + 8 + 8 +
+        (5 * 8) + (5 * 8) + // rating histograms
+        2 + // average_rating
+        8 + 8 + // deliveries
+        8 + 8 + // dispute outcomes
+        8 + 8 + // role scores
+        8; // last_activity_at
+
+    /// Adds a review received as freelancer (or as client) and refreshes the scores.
+    pub fn record_rating(&mut self, rating: u8, as_freelancer: bool) {
+        let histogram = if as_freelancer {
+            &mut self.freelancer_ratings
+        } else {
+            &mut self.client_ratings
+        };
+        let bucket = &mut histogram[(rating - 1) as usize];
+        *bucket = bucket.checked_add(HISTORY_UNIT).unwrap();
+        self.refresh_reputation();
+    }
+
+    /// Fades the recorded history by the time since the last activity, so
+    /// the scores drift back to the baseline, and marks the user active.
+    /// Called before any reputation change; stored scores of idle users are
+    /// only brought up to date the next time their account is touched.
+    pub fn touch(&mut self, now: i64, half_life: i64) {
+        let elapsed = now.saturating_sub(self.last_activity_at);
+        if elapsed > 0 {
+            for count in self
+                .freelancer_ratings
+                .iter_mut()
+                .chain(self.client_ratings.iter_mut())
+                .chain([
+                    &mut self.on_time_deliveries,
+                    &mut self.late_deliveries,
+                    &mut self.dispute_points_won,
+                    &mut self.dispute_points_lost,
+                ])
+            {
+                *count = decay_count(*count, elapsed, half_life);
+            }
+            self.refresh_reputation();
+        }
+        self.last_activity_at = now;
+    }
+
+    /// Counts a finished job as delivered on time or late.
+    pub fn record_delivery(&mut self, on_time: bool) {
+        if on_time {
+            self.on_time_deliveries = self.on_time_deliveries.checked_add(HISTORY_UNIT).unwrap();
+        } else {
+            self.late_deliveries = self.late_deliveries.checked_add(HISTORY_UNIT).unwrap();
+        }
+        self.refresh_reputation();
+    }
+
+    /// Counts a resolved dispute in which the user was awarded `share`
+    /// percent of the escrow.
+    pub fn record_dispute(&mut self, share: u8) {
+        let won = share.min(100) as u64 * HISTORY_UNIT / 100;
+        self.disputes_resolved = self.disputes_resolved.checked_add(1).unwrap();
+        self.dispute_points_won = self.dispute_points_won.checked_add(won).unwrap();
+        self.dispute_points_lost = self.dispute_points_lost.checked_add(HISTORY_UNIT - won).unwrap();
+        self.refresh_reputation();
+    }
+
+    /// Recomputes the role scores from the recorded history.
+    ///
+    /// Freelancer: 60% rating, 25% on-time delivery, 15% dispute record.
+    /// Client: 80% rating, 20% dispute record. Each part starts at the
+    /// baseline and moves towards the user's actual record as it grows.
+    pub fn refresh_reputation(&mut self) {
+        let (count, stars) = rating_totals(&self.freelancer_ratings, &self.client_ratings);
+        self.average_rating = (stars * 100).checked_div(count).unwrap_or(0) as u16;
+        let disputes = ratio_score(self.dispute_points_won, self.dispute_points_lost, HISTORY_UNIT);
+        self.freelancer_score = (rating_score(&self.freelancer_ratings) * 60
+            + ratio_score(self.on_time_deliveries, self.late_deliveries, HISTORY_UNIT) * 25
+            + disputes * 15)
+            / 100;
+        self.client_score = (rating_score(&self.client_ratings) * 80 + disputes * 20) / 100;
+        self.reputation = match (self.is_freelancer, self.is_client) {
+            (true, false) => self.freelancer_score,
+            (false, true) => self.client_score,
+            _ => (self.freelancer_score + self.client_score) / 2,
+        };
+    }
+}
+
+/// Number of reviews and total stars over both histograms.
+fn rating_totals(freelancer: &[u64; 5], client: &[u64; 5]) -> (u64, u64) {
+    freelancer
+        .iter()
+        .zip(client.iter())
+        .enumerate()
+        .fold((0, 0), |(count, stars), (index, (f, c))| {
+            (count + f + c, stars + (f + c) * (index as u64 + 1))
+        })
+}
+
+/// Smoothed star average mapped onto 0..=MAX_REPUTATION (1 star = 0, 5 stars = max).
+fn rating_score(histogram: &[u64; 5]) -> u64 {
+    let (count, stars) = rating_totals(histogram, &[0; 5]);
+    let count = count + RATING_PRIOR * HISTORY_UNIT;
+    let stars = stars + RATING_PRIOR * HISTORY_UNIT * 3;
+    (stars - count) * MAX_REPUTATION / (count * 4)
+}
+
+/// 2^(-1/2^k) for k = 1..=32 as 32-bit fixed point fractions.
+const HALF_LIFE_ROOTS: [u64; 32] = [
+    3037000500, 3611622603, 3938502376, 4112874773, 4202935003, 4248701965, 4271771996, 4283353945,
+    4289156690, 4292061010, 4293513907, 4294240540, 4294603903, 4294785595, 4294876445, 4294921870,
+    4294944583, 4294955939, 4294961618, 4294964457, 4294965876, 4294966586, 4294966941, 4294967119,
+    4294967207, 4294967252, 4294967274, 4294967285, 4294967290, 4294967293, 4294967295, 4294967295,
+];
+
+/// Decays `count` exponentially, halving it for every `half_life` in `elapsed`.
+/// Decaying by `a` and then `b` matches decaying by `a + b` up to fixed point
+/// rounding, so the result does not depend on how often the account is touched.
+fn decay_count(count: u64, elapsed: i64, half_life: i64) -> u64 {
+    let periods = elapsed / half_life;
+    if periods >= 64 {
+        return 0;
+    }
+    // Remainder of the last half-life in 1/2^32 steps, its factor is built
+    // up first so `count` is only rounded once
+    let fraction = (((elapsed % half_life) as u128) << 32) / half_life as u128;
+    let mut factor: u128 = 1 << 32;
+    for (bit, root) in HALF_LIFE_ROOTS.iter().enumerate() {
+        if fraction & (1 << (31 - bit)) != 0 {
+            factor = (factor * *root as u128 + (1 << 31)) >> 32;
+        }
+    }
+    (((count as u128 * factor + (1 << 31)) >> 32) >> periods) as u64
+}
+
+/// Smoothed share of good outcomes on 0..=MAX_REPUTATION, baseline when empty.
+/// `unit` is the weight of a single outcome, one good and one bad are assumed.
+fn ratio_score(good: u64, bad: u64, unit: u64) -> u64 {
+    (good + unit) * MAX_REPUTATION / (good + bad + 2 * unit)
 }
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Resume {
@@ -111,6 +325,158 @@ impl PortfolioItem {
         4 + 100 + // url
         4 + 100; // image_url
 }
+// Platform wide settings, a single [b"config"] account
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub platform_fee_bps: u16,
+    pub max_voters: u64,
+    pub max_voting_period: i64,
+    pub max_offer_duration: i64,
+    pub deadline_grace_period: i64,
+    pub review_period: i64,
+    pub reputation_half_life: i64,
+    pub juror_min_account_age: i64,
+    pub juror_min_completed_jobs: u64,
+    pub juror_min_reputation: u64,
+    pub stake_mint: Option<Pubkey>, // None = jurors stake SOL
+    pub juror_stake: u64,
+    pub reveal_period: i64,
+    // A round below `quorum` revealed votes is extended up to
+    // `max_voting_extensions` times, then pays out `fallback_raiser_share`
+    pub quorum: u64,
+    pub max_voting_extensions: u8,
+    pub fallback_raiser_share: u8,
+    // Appeals, the second round needs `appeal_quorum` revealed votes and
+    // jurors meeting the stricter appeal requirements
+    pub appeal_period: i64,
+    pub appeal_bond_bps: u16,
+    pub appeal_quorum: u64,
+    pub appeal_juror_min_completed_jobs: u64,
+    pub appeal_juror_min_reputation: u64,
+    // Jurors drawn from the registry for each round
+    pub panel_size: u64,
+    pub appeal_panel_size: u64,
+    pub dispute_fee: u64, // paid by the raiser in the juror stake currency
+    // Unclaimed juror payouts can be swept to the treasury this long after
+    // the dispute is resolved
+    pub stake_claim_period: i64,
+    // Jurors who miss a vote are kept off panels this long
+    pub juror_suspension_period: i64,
+    pub paused: u8,
+    pub bump: u8,
+}
+impl Config {
+    pub const LEN: usize = 
+        32 + // admin
+        32 + // treasury
+        2 + // platform_fee_bps
+        8 + // max_voters
+        8 + // max_voting_period
+        8 + // max_offer_duration
+        8 + // deadline_grace_period
+        8 + // review_period
+        8 + // reputation_half_life
+        8 + // juror_min_account_age
+        8 + // juror_min_completed_jobs
+        8 + // juror_min_reputation
+        1 + 32 + // stake_mint
+        8 + // juror_stake
+        8 + // reveal_period
+        8 + // quorum
+        1 + // max_voting_extensions
+        1 + // fallback_raiser_share
+        8 + // appeal_period
+        2 + // appeal_bond_bps
+        8 + // appeal_quorum
+        8 + // appeal_juror_min_completed_jobs
+        8 + // appeal_juror_min_reputation
+        8 + // panel_size
+        8 + // appeal_panel_size
+        8 + // dispute_fee
+        8 + // stake_claim_period
+        8 + // juror_suspension_period
+        1 + // paused
+        1; // bump
+
+    /// Fails with `ProgramPaused` while the given instruction group is paused.
+    pub fn require_not_paused(&self, group: u8) -> Result<()> {
+        require!(self.paused & group == 0, ErrorCode::ProgramPaused);
+        Ok(())
+    }
+
+    /// Checks that a user meets the requirements for voting in the given
+    /// dispute round, appeal rounds use the stricter appeal thresholds.
+    pub fn require_eligible_juror(&self, juror: &User, now: i64, round: u8) -> Result<()> {
+        let (min_completed_jobs, min_reputation) = if round > 1 {
+            (self.appeal_juror_min_completed_jobs, self.appeal_juror_min_reputation)
+        } else {
+            (self.juror_min_completed_jobs, self.juror_min_reputation)
+        };
+        require!(
+            now.saturating_sub(juror.created_at) >= self.juror_min_account_age,
+            ErrorCode::JurorAccountTooNew
+        );
+        require!(
+            juror.completed_jobs >= min_completed_jobs,
+            ErrorCode::JurorTooInexperienced
+        );
+        require!(
+            juror.reputation >= min_reputation,
+            ErrorCode::JurorReputationTooLow
+        );
+        Ok(())
+    }
+
+    /// Votes a dispute round needs, appeal rounds use the larger appeal quorum.
+    pub fn quorum_for(&self, round: u8) -> u64 {
+        if round > 1 {
+            self.appeal_quorum
+        } else {
+            self.quorum
+        }
+    }
+
+    /// Platform fee owed on a release of `amount`.
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        ((amount as u128) * (self.platform_fee_bps as u128) / 10_000) as u64
+    }
+}
+
+/// Admin changes to the platform config, `None` keeps the current value.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigUpdate {
+    pub admin: Option<Pubkey>,
+    pub treasury: Option<Pubkey>,
+    pub platform_fee_bps: Option<u16>,
+    pub max_voters: Option<u64>,
+    pub max_voting_period: Option<i64>,
+    pub max_offer_duration: Option<i64>,
+    pub deadline_grace_period: Option<i64>,
+    pub review_period: Option<i64>,
+    pub reputation_half_life: Option<i64>,
+    pub juror_min_account_age: Option<i64>,
+    pub juror_min_completed_jobs: Option<u64>,
+    pub juror_min_reputation: Option<u64>,
+    pub stake_mint: Option<Option<Pubkey>>, // Some(None) switches stakes to SOL
+    pub juror_stake: Option<u64>,
+    pub reveal_period: Option<i64>,
+    pub quorum: Option<u64>,
+    pub max_voting_extensions: Option<u8>,
+    pub fallback_raiser_share: Option<u8>,
+    pub appeal_period: Option<i64>,
+    pub appeal_bond_bps: Option<u16>,
+    pub appeal_quorum: Option<u64>,
+    pub appeal_juror_min_completed_jobs: Option<u64>,
+    pub appeal_juror_min_reputation: Option<u64>,
+    pub panel_size: Option<u64>,
+    pub appeal_panel_size: Option<u64>,
+    pub dispute_fee: Option<u64>,
+    pub stake_claim_period: Option<i64>,
+    pub juror_suspension_period: Option<i64>,
+}
+
 // ALl related to Jobs
 #[account]
 pub struct Job {
@@ -125,7 +491,7 @@ pub struct Job {
     pub status: JobStatus,
     pub created_at: i64,
     pub updated_at: i64,    
-    pub bidders: Vec<Bid>,
+    pub bid_count: u64,
     pub reviews: Vec<Review>,
     pub dispute: Option<Dispute>,
     pub skills: Vec<String>,
@@ -138,7 +504,15 @@ pub struct Job {
     pub work_submitted_at: Option<i64>,
     pub work_approved: bool,
     pub work_approved_at: Option<i64>,
-    pub revision_request: Option<String>
+    pub revision_request: Option<String>,
+    pub milestones: Vec<Milestone>,
+    pub mint: Option<Pubkey>,
+    pub cancellation_consent: bool,
+    pub offered_amount: u64,
+    pub offer_expires_at: Option<i64>,
+    pub screening_questions: Vec<String>,
+    pub terms_updated_at: Option<i64>,
+    pub revision_requested_at: Option<i64>,
 }
 impl Job {
     pub const LEN: usize = 
@@ -153,8 +527,8 @@ impl Job {
         1 + // status
         8 + // created_at
         8 + // updated_at
-        4 + (50 * Bid::LEN) + // bidders vec
-        4 + (10 * Review::LEN) + // reviews vec
+        8 + // bid_count
+        4 + (MAX_REVIEWS * Review::LEN) + // reviews vec
         1 + (1 + Dispute::LEN) + // dispute
         4 + (10 * (4 + 50)) + // skills vec
         4 + 50 + // category
@@ -165,7 +539,52 @@ impl Job {
         4 + 1000 + // work_submission_description
         1 + 8 + // work_submitted_at
         1 + // work_approved
-        1 + 8; // work_approved_at
+        1 + 8 + // work_approved_at
+        1 + (4 + 500) + // revision_request
+        4 + (MAX_MILESTONES * Milestone::LEN) + // milestones vec
+        1 + 32 + // mint (None = native SOL)
+        1 + // cancellation_consent
+        8 + // offered_amount
+        1 + 8 + // offer_expires_at
+        4 + (MAX_SCREENING_QUESTIONS * (4 + 100)) + // screening_questions vec
+        1 + 8 + // terms_updated_at
+        1 + 8; // revision_requested_at
+}
+// Accounts created through CPI are limited to 10 KiB
+const _: () = assert!(8 + Job::LEN <= 10 * 1024);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MilestoneInput {
+    pub title: String,
+    pub amount: u64,
+    pub due_date: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Milestone {
+    pub title: String,
+    pub amount: u64,
+    pub due_date: i64,
+    pub status: MilestoneStatus,
+    pub submitted_at: Option<i64>,
+    pub approved_at: Option<i64>,
+}
+impl Milestone {
+    pub const LEN: usize =
+        4 + 50 + // title
+        8 + // amount
+        8 + // due_date
+        1 + // status
+        1 + 8 + // submitted_at
+        1 + 8; // approved_at
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    Submitted,
+    RevisionRequested,
+    Approved,
 }
 
 #[account]
@@ -177,6 +596,28 @@ impl JobCounter {
     pub const LEN: usize = 8; // Just the count (u64)
 }
 
+// Marks that two users have worked together, a [b"collab", first, second] PDA
+// with the keys in `Collaboration::pair` order
+#[account]
+pub struct Collaboration {
+    pub parties: [Pubkey; 2],
+    pub jobs: u64,
+    pub last_job_at: i64,
+    pub bump: u8,
+}
+impl Collaboration {
+    pub const LEN: usize = (2 * 32) + 8 + 8 + 1;
+
+    /// Orders two users so that either order maps to the same PDA.
+    pub fn pair<'a>(a: &'a Pubkey, b: &'a Pubkey) -> [&'a Pubkey; 2] {
+        if a <= b {
+            [a, b]
+        } else {
+            [b, a]
+        }
+    }
+}
+
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Review {
@@ -184,6 +625,8 @@ pub struct Review {
     pub rating: u8,
     pub comment: String,
     pub created_at: i64,
+    pub reply: Option<String>, // public answer from the reviewed party
+    pub replied_at: Option<i64>,
 }
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Dispute {
@@ -193,16 +636,42 @@ pub struct Dispute {
     pub status: DisputeStatus,
     pub created_at: i64,
     pub resolved_at: Option<i64>,
-    pub resolution: Option<String>,
+    pub resolution: Option<DisputeResolution>,
 
-    // for voters
+    // for voters of the current round, commits are accepted until
+    // `commit_end` and revealed until `voting_end`; the vote counts only
+    // include revealed votes
+    pub round: u8, // 1, or 2 once appealed
+    // The round's jury panel is drawn from the hash of the first block at or
+    // after `selection_slot`, voting opens once it is selected
+    pub selection_slot: u64,
+    pub panel_selected: bool,
+    pub panel: Vec<PanelSeat>,
+    // Jurors seated in earlier rounds, who cannot be drawn again since their
+    // vote record stays open until the dispute is resolved
+    pub prior_jurors: Vec<Pubkey>,
     pub voting_start: i64,
+    pub commit_end: i64,
     pub voting_end: i64,
-    pub votes_for_raiser: u64,
-    pub votes_for_against: u64,
-    pub voters: Vec<Pubkey>,
+    pub commit_count: u64,
+    pub votes: [u64; RULING_BRACKETS], // revealed votes per raiser share bracket
+    pub extensions: u8, // times this round was extended for lack of quorum
     pub raiser_role: DisputeRole,
     pub against_role: DisputeRole,
+
+    // Juror stakes, held in the [b"dispute_vault", job] PDA
+    pub stake_mint: Option<Pubkey>,
+    pub juror_stake: u64,
+    pub stake_committed: u64,
+    pub stakes: [u64; RULING_BRACKETS], // revealed stakes per bracket
+    pub filing_fee: u64, // held in the vault until the dispute is finalized
+    pub rulings: Vec<JuryRuling>, // settled rounds, in order
+    pub appeal: Option<Appeal>,
+
+    // Evidence PDAs in submission order, accepted until `commit_end`
+    pub evidence: Vec<Pubkey>,
+    pub raiser_evidence: u8,
+    pub against_evidence: u8,
 }
 impl Dispute {
     pub const LEN: usize = 32 + 
@@ -211,14 +680,252 @@ impl Dispute {
         1 + // status
         8 + // created_at
         1 + 8 + // resolved_at (Option)
-        1 + (4 + 500) + // resolution (Option)
+        1 + DisputeResolution::LEN + // resolution (Option)
+        1 + // round
+        8 + // selection_slot
+        1 + // panel_selected
+        4 + (MAX_PANEL_SIZE * PanelSeat::LEN) + // panel vec
+        4 + (MAX_PANEL_SIZE * (MAX_DISPUTE_ROUNDS - 1) * 32) + // prior_jurors vec
         8 + // voting_start
+        8 + // commit_end
         8 + // voting_end
-        8 + // votes_for
-        8 + // votes_against
-        4 + (100 * 32)+ // voters vec (max 100 voters)
+        8 + // commit_count
+        (RULING_BRACKETS * 8) + // votes
+        1 + // extensions
         1 + //raiser role
-        1;  //against role
+        1 + //against role
+        1 + 32 + // stake_mint
+        8 + // juror_stake
+        8 + // stake_committed
+        (RULING_BRACKETS * 8) + // stakes
+        8 + // filing_fee
+        4 + (MAX_DISPUTE_ROUNDS * JuryRuling::LEN) + // rulings vec
+        1 + Appeal::LEN + // appeal (Option)
+        4 + (MAX_EVIDENCE * 32) + // evidence vec
+        1 + 1; // evidence per party
+
+    /// Stake-weighted median of the revealed votes as the raiser's share in
+    /// percent, halfway between the two middle brackets on an even split.
+    /// `None` while no vote has been revealed.
+    pub fn median_share(&self) -> Option<u8> {
+        let total: u64 = self.stakes.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let (mut lower, mut upper) = (None, None);
+        let mut cumulative = 0u128;
+        for (bracket, stake) in self.stakes.iter().enumerate() {
+            cumulative += *stake as u128;
+            if lower.is_none() && cumulative * 2 >= total as u128 {
+                lower = Some(bracket as u8);
+            }
+            if cumulative * 2 > total as u128 {
+                upper = Some(bracket as u8);
+                break;
+            }
+        }
+        Some((lower? + upper?) * RULING_STEP / 2)
+    }
+
+    /// Bitmask of the brackets within RULING_TOLERANCE of `share`.
+    pub fn coherent_brackets(share: u8) -> u16 {
+        (0..RULING_BRACKETS as u8)
+            .filter(|bracket| (bracket * RULING_STEP).abs_diff(share) <= RULING_TOLERANCE)
+            .fold(0, |mask, bracket| mask | (1 << bracket))
+    }
+
+    /// Whether the current round fell short of `quorum` commits and can
+    /// still be extended. Reveals stay closed while this holds, so no vote
+    /// is public when commits reopen.
+    pub fn needs_extension(&self, quorum: u64, max_extensions: u8) -> bool {
+        self.commit_count < quorum && self.extensions < max_extensions
+    }
+
+    /// Schedules a new draw for the seats of absent jurors at a slot after
+    /// `slot`. Commits reopen for another voting period of the round's length
+    /// once the panel is selected, pending commits are revealed after that.
+    pub fn extend_voting(&mut self, slot: u64) {
+        self.selection_slot = slot + SELECTION_DELAY_SLOTS;
+        self.panel_selected = false;
+        self.extensions += 1;
+    }
+
+    /// Moves the current round's commit and reveal phases to start at `now`.
+    pub fn open_voting(&mut self, now: i64) {
+        let voting_period = self.voting_end - self.voting_start;
+        let reveal_period = self.voting_end - self.commit_end;
+        self.voting_start = now;
+        self.commit_end = now + voting_period - reveal_period;
+        self.voting_end = now + voting_period;
+    }
+
+    /// Fills the panel up to `seats` with registered jurors drawn at random
+    /// using `entropy`, skipping the parties, jurors seated in this or an
+    /// earlier round, suspended jurors and, on appeal, jurors below the
+    /// appeal thresholds.
+    /// Returns the newly seated jurors.
+    pub fn draw_panel(
+        &mut self,
+        registry: &[RegisteredJuror],
+        seats: usize,
+        entropy: &[u8; 32],
+    ) -> Vec<Pubkey> {
+        let mut candidates: Vec<&Pubkey> = registry
+            .iter()
+            .filter(|entry| entry.suspended_until == 0)
+            .filter(|entry| self.round == 1 || entry.appeal_eligible)
+            .map(|entry| &entry.juror)
+            .filter(|juror| **juror != self.raiser && **juror != self.against)
+            .filter(|juror| !self.panel.iter().any(|seat| seat.juror == **juror))
+            .filter(|juror| !self.prior_jurors.contains(juror))
+            .collect();
+        let mut drawn = Vec::new();
+        while self.panel.len() < seats.min(MAX_PANEL_SIZE) && !candidates.is_empty() {
+            let digest: [u8; 32] = Sha256::new()
+                .chain_update(entropy)
+                .chain_update([self.round, self.panel.len() as u8])
+                .finalize()
+                .into();
+            let pick = u64::from_le_bytes(digest[..8].try_into().unwrap());
+            let juror = *candidates.swap_remove((pick % candidates.len() as u64) as usize);
+            self.panel.push(PanelSeat {
+                juror,
+                committed: false,
+            });
+            drawn.push(juror);
+        }
+        drawn
+    }
+
+    /// Removes the panel members who did not commit a vote and returns them.
+    pub fn remove_absent_jurors(&mut self) -> Vec<Pubkey> {
+        let absent = self
+            .panel
+            .iter()
+            .filter(|seat| !seat.committed)
+            .map(|seat| seat.juror)
+            .collect();
+        self.panel.retain(|seat| seat.committed);
+        absent
+    }
+
+    /// Rules on the current round and settles its stakes. Jurors within
+    /// RULING_TOLERANCE of the ruling split the outliers' and the unrevealed
+    /// stakes. If none voted that close, every revealed juror keeps their
+    /// stake and only the unrevealed ones are shared. Below `quorum` revealed
+    /// votes the round reaches no ruling and every commit is refunded.
+    pub fn tally(&self, quorum: u64) -> JuryRuling {
+        let votes_revealed: u64 = self.votes.iter().sum();
+        let median = self.median_share().filter(|_| votes_revealed >= quorum);
+        let Some(share) = median else {
+            return JuryRuling {
+                raiser_share: None,
+                votes_revealed,
+                extensions: self.extensions,
+                coherent_brackets: 0,
+                coherent_stake: self.stake_committed,
+                reward_pool: 0,
+                unclaimed_payouts: self.commit_count,
+                unclaimed_amount: self.stake_committed,
+            };
+        };
+        // Revealed votes and stake in the brackets of `mask`
+        let totals = |mask: u16| {
+            (0..RULING_BRACKETS)
+                .filter(|b| mask & (1 << b) != 0)
+                .fold((0, 0), |(votes, stake), b| (votes + self.votes[b], stake + self.stakes[b]))
+        };
+        let mut coherent_brackets = Dispute::coherent_brackets(share);
+        if totals(coherent_brackets).0 == 0 {
+            coherent_brackets = (0..RULING_BRACKETS)
+                .filter(|b| self.votes[*b] > 0)
+                .fold(0, |mask, b| mask | (1 << b));
+        }
+        let (coherent_votes, coherent_stake) = totals(coherent_brackets);
+        JuryRuling {
+            raiser_share: Some(share),
+            votes_revealed,
+            extensions: self.extensions,
+            coherent_brackets,
+            coherent_stake,
+            reward_pool: self.stake_committed - coherent_stake,
+            unclaimed_payouts: coherent_votes,
+            unclaimed_amount: self.stake_committed,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PanelSeat {
+    pub juror: Pubkey,
+    pub committed: bool,
+}
+impl PanelSeat {
+    pub const LEN: usize = 32 + 1;
+}
+
+/// The ruling of one jury round and the settlement of its stakes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct JuryRuling {
+    pub raiser_share: Option<u8>, // percent, None when the round reached no ruling
+    pub votes_revealed: u64,
+    pub extensions: u8,
+    // Brackets whose jurors keep their stake and share the reward pool,
+    // 0 when every commit is refunded
+    pub coherent_brackets: u16,
+    pub coherent_stake: u64,
+    pub reward_pool: u64, // slashed outlier and unrevealed stakes
+    pub unclaimed_payouts: u64,
+    pub unclaimed_amount: u64, // left in the vault for this round
+}
+impl JuryRuling {
+    pub const LEN: usize = 2 + 8 + 1 + 2 + 8 + 8 + 8 + 8;
+}
+
+/// An appeal of the first ruling, the bond is held in the job escrow.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Appeal {
+    pub appellant: Pubkey,
+    pub bond: u64,
+    pub created_at: i64,
+}
+impl Appeal {
+    pub const LEN: usize = 32 + 8 + 8;
+
+    /// Who gets the bond once the dispute is settled at `raiser_share`, and
+    /// whether that is a refund. The appellant only gets it back if the
+    /// appeal improved on the appealed `appealed_share`, otherwise it goes to
+    /// the other party.
+    pub fn bond_recipient(
+        &self,
+        raiser: Pubkey,
+        against: Pubkey,
+        appealed_share: u8,
+        raiser_share: u8,
+    ) -> (Pubkey, bool) {
+        let by_raiser = self.appellant == raiser;
+        let refunded = if by_raiser {
+            raiser_share > appealed_share
+        } else {
+            raiser_share < appealed_share
+        };
+        let recipient = if refunded == by_raiser { raiser } else { against };
+        (recipient, refunded)
+    }
+}
+
+/// How escrow was split when a dispute was finalized.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DisputeResolution {
+    pub raiser_share: u8, // percent of escrow awarded to the raiser
+    pub raiser_amount: u64,
+    pub against_amount: u64,
+    // The raiser gets the filing fee back on a win, or if no jury ruled,
+    // otherwise it goes to the coherent jurors of the final round
+    pub filing_fee_refunded: bool,
+}
+impl DisputeResolution {
+    pub const LEN: usize = 1 + 8 + 8 + 1;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -239,19 +946,391 @@ pub enum JobStatus {
     Completed,
     Cancelled,
     Disputed,
+    Offered,
 }
 
 
 impl Review {
-    pub const LEN: usize = 32 + 1 + (4 + 500) + 8;
+    pub const LEN: usize = 32 + 1 + (4 + MAX_REVIEW_LENGTH) + 8 +
+        1 + (4 + MAX_REPLY_LENGTH) + // reply
+        1 + 8; // replied_at
+}
+
+// A juror's vote and stake, the [b"vote", job, voter] PDA
+#[account]
+pub struct VoteRecord {
+    pub job: Pubkey,
+    pub voter: Pubkey,
+    pub commitment: [u8; 32],
+    pub round: u8,
+    pub revealed: bool,
+    pub bracket: u8, // raiser share in RULING_STEP percent, only meaningful once revealed
+    pub stake: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+impl VoteRecord {
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 1 + 1 + 8 + 8 + 1;
+
+    /// `sha256(job || voter || round || bracket || salt)`, where the bracket is
+    /// the raiser's share of escrow in RULING_STEP percent (0..=10). Jurors
+    /// commit this and reveal bracket and salt later. Binding the job, voter
+    /// and round keeps one juror's commitment from being copied by another.
+    pub fn commitment_for(
+        job: &Pubkey,
+        voter: &Pubkey,
+        round: u8,
+        bracket: u8,
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(job)
+            .chain_update(voter)
+            .chain_update([round])
+            .chain_update([bracket])
+            .chain_update(salt)
+            .finalize()
+            .into()
+    }
+}
+
+// A document submitted by either party to a dispute, the
+// [b"evidence", job, index] PDA listed in `Dispute::evidence`
+#[account]
+pub struct Evidence {
+    pub job: Pubkey,
+    pub submitter: Pubkey,
+    pub index: u8,
+    pub uri: String,
+    pub content_hash: [u8; 32], // sha256 of the document behind `uri`
+    pub statement: String,
+    pub created_at: i64,
+    pub bump: u8,
+}
+impl Evidence {
+    pub const LEN: usize = 32 + 32 + 1 +
+        4 + MAX_EVIDENCE_URI_LENGTH +
+        32 +
+        4 + MAX_EVIDENCE_STATEMENT_LENGTH +
+        8 + 1;
+}
+
+// Users who opted in to jury duty, the single [b"juror_registry"] account
+#[account]
+pub struct JurorRegistry {
+    pub jurors: Vec<RegisteredJuror>,
+    pub bump: u8,
+}
+impl JurorRegistry {
+    pub const LEN: usize = 4 + (MAX_REGISTERED_JURORS * RegisteredJuror::LEN) + 1;
+
+    pub fn find(&self, juror: &Pubkey) -> Option<usize> {
+        self.jurors.iter().position(|entry| entry.juror == *juror)
+    }
+
+    /// Keeps the given jurors out of panels until `until`. They stay listed
+    /// so they cannot register again in the meantime.
+    pub fn suspend(&mut self, jurors: &[Pubkey], until: i64) {
+        for entry in self.jurors.iter_mut().filter(|entry| jurors.contains(&entry.juror)) {
+            entry.suspended_until = until;
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RegisteredJuror {
+    pub juror: Pubkey,
+    // Whether the juror met the appeal thresholds when registering
+    pub appeal_eligible: bool,
+    // Set when the juror missed a vote, 0 while active. Suspended jurors are
+    // not drawn and have to register again once it has passed.
+    pub suspended_until: i64,
+}
+impl RegisteredJuror {
+    pub const LEN: usize = 32 + 1 + 8;
+}
+
+#[account]
 pub struct Bid {
+    pub job: Pubkey,
     pub freelancer: Pubkey,
     pub proposed_amount: u64,
     pub timestamp: i64,
+    pub cover_letter: String,
+    pub estimated_delivery: i64,
+    pub screening_answers: Vec<String>,
+    pub selected: bool, // set once the freelancer accepted the job's offer
 }
 impl Bid {
-    pub const LEN: usize = 32 + 8 + 8;
+    pub const LEN: usize = 32 + 32 + 8 + 8 +
+        4 + 1000 + // cover_letter
+        8 + // estimated_delivery
+        4 + (MAX_SCREENING_QUESTIONS * (4 + 300)) + // screening_answers vec
+        1; // selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An open first-round dispute with the given revealed `(bracket, votes, stake)`
+    /// entries and `unrevealed` extra commits of `stake` each.
+    fn dispute_with(revealed: &[(usize, u64, u64)], unrevealed: u64, stake: u64) -> Dispute {
+        let mut votes = [0; RULING_BRACKETS];
+        let mut stakes = [0; RULING_BRACKETS];
+        for &(bracket, count, amount) in revealed {
+            votes[bracket] += count;
+            stakes[bracket] += amount;
+        }
+        let commit_count = votes.iter().sum::<u64>() + unrevealed;
+        let stake_committed = stakes.iter().sum::<u64>() + unrevealed * stake;
+        Dispute {
+            raiser: Pubkey::new_unique(),
+            against: Pubkey::new_unique(),
+            reason: String::new(),
+            status: DisputeStatus::Open,
+            created_at: 0,
+            resolved_at: None,
+            resolution: None,
+            round: 1,
+            selection_slot: 0,
+            panel_selected: true,
+            panel: Vec::new(),
+            prior_jurors: Vec::new(),
+            voting_start: 0,
+            commit_end: 0,
+            voting_end: 0,
+            commit_count,
+            votes,
+            extensions: 0,
+            raiser_role: DisputeRole::Client,
+            against_role: DisputeRole::Freelancer,
+            stake_mint: None,
+            juror_stake: stake,
+            stake_committed,
+            stakes,
+            filing_fee: 0,
+            rulings: Vec::new(),
+            appeal: None,
+            evidence: Vec::new(),
+            raiser_evidence: 0,
+            against_evidence: 0,
+        }
+    }
+
+    #[test]
+    fn median_share_without_reveals_is_none() {
+        assert_eq!(dispute_with(&[], 3, 10).median_share(), None);
+    }
+
+    #[test]
+    fn median_share_takes_the_middle_of_an_odd_split() {
+        let dispute = dispute_with(&[(3, 1, 10), (5, 1, 10), (8, 1, 10)], 0, 10);
+        assert_eq!(dispute.median_share(), Some(50));
+    }
+
+    #[test]
+    fn median_share_averages_the_middle_brackets_of_an_even_split() {
+        let dispute = dispute_with(&[(2, 1, 10), (4, 1, 10)], 0, 10);
+        assert_eq!(dispute.median_share(), Some(30));
+        let dispute = dispute_with(&[(0, 1, 10), (10, 1, 10)], 0, 10);
+        assert_eq!(dispute.median_share(), Some(50));
+    }
+
+    #[test]
+    fn median_share_is_stake_weighted() {
+        let dispute = dispute_with(&[(0, 1, 30), (10, 2, 20)], 0, 10);
+        assert_eq!(dispute.median_share(), Some(0));
+    }
+
+    #[test]
+    fn coherent_brackets_are_within_tolerance() {
+        assert_eq!(Dispute::coherent_brackets(50), 0b000_0111_0000);
+        assert_eq!(Dispute::coherent_brackets(0), 0b000_0000_0011);
+        assert_eq!(Dispute::coherent_brackets(100), 0b110_0000_0000);
+        assert_eq!(Dispute::coherent_brackets(55), 0b000_0110_0000);
+    }
+
+    #[test]
+    fn tally_without_reveals_reaches_no_ruling() {
+        let ruling = dispute_with(&[], 2, 10).tally(1);
+        assert_eq!(ruling.raiser_share, None);
+        assert_eq!(ruling.unclaimed_payouts, 2);
+        assert_eq!(ruling.unclaimed_amount, 20);
+    }
+
+    #[test]
+    fn tally_slashes_outliers_and_unrevealed_commits() {
+        let ruling = dispute_with(&[(5, 2, 20), (0, 1, 10)], 1, 10).tally(3);
+        assert_eq!(ruling.raiser_share, Some(50));
+        assert_eq!(ruling.coherent_brackets, Dispute::coherent_brackets(50));
+        assert_eq!(ruling.coherent_stake, 20);
+        assert_eq!(ruling.reward_pool, 20);
+        assert_eq!(ruling.unclaimed_payouts, 2);
+        assert_eq!(ruling.unclaimed_amount, 40);
+    }
+
+    #[test]
+    fn tally_falls_back_to_all_revealed_brackets_on_a_split_jury() {
+        // A 0/100 split rules 50%, which no revealed vote is close to
+        let ruling = dispute_with(&[(0, 1, 10), (10, 1, 10)], 1, 10).tally(2);
+        assert_eq!(ruling.raiser_share, Some(50));
+        assert_eq!(ruling.coherent_brackets, (1 << 0) | (1 << 10));
+        assert_eq!(ruling.coherent_stake, 20);
+        assert_eq!(ruling.reward_pool, 10);
+        assert_eq!(ruling.unclaimed_payouts, 2);
+    }
+
+    #[test]
+    fn ratio_score_starts_at_the_baseline() {
+        assert_eq!(ratio_score(0, 0, 1), REPUTATION_BASELINE);
+        assert_eq!(ratio_score(0, 0, HISTORY_UNIT), REPUTATION_BASELINE);
+    }
+
+    #[test]
+    fn ratio_score_moves_towards_the_record() {
+        assert_eq!(ratio_score(3, 1, 1), 66);
+        assert_eq!(ratio_score(1, 3, 1), 33);
+        assert_eq!(ratio_score(98, 0, 1), 99);
+        assert_eq!(ratio_score(3 * HISTORY_UNIT, HISTORY_UNIT, HISTORY_UNIT), 66);
+    }
+
+    #[test]
+    fn decay_count_halves_every_half_life() {
+        let half_life = 90 * 24 * 60 * 60;
+        assert_eq!(decay_count(1_000, 0, half_life), 1_000);
+        assert_eq!(decay_count(1_000, half_life, half_life), 500);
+        assert_eq!(decay_count(1_000, 3 * half_life, half_life), 125);
+        assert_eq!(decay_count(u64::MAX, 64 * half_life, half_life), 0);
+    }
+
+    #[test]
+    fn decay_count_is_exponential_within_a_half_life() {
+        let half_life = 90 * 24 * 60 * 60;
+        // 2^(-1/2) = 0.7071...
+        let decayed = decay_count(HISTORY_UNIT, half_life / 2, half_life);
+        assert!((707_106..=707_107).contains(&decayed), "{decayed}");
+    }
+
+    #[test]
+    fn decay_count_does_not_depend_on_touch_frequency() {
+        let day = 24 * 60 * 60;
+        let half_life = 90 * day;
+        let once = decay_count(5 * HISTORY_UNIT, half_life, half_life);
+        let daily = (0..90).fold(5 * HISTORY_UNIT, |count, _| decay_count(count, day, half_life));
+        assert!(daily.abs_diff(once) * 10_000 <= once, "{daily} vs {once}");
+        let hourly = (0..90 * 24)
+            .fold(5 * HISTORY_UNIT, |count, _| decay_count(count, 60 * 60, half_life));
+        assert!(hourly.abs_diff(once) * 10_000 <= once, "{hourly} vs {once}");
+    }
+
+    #[test]
+    fn tally_below_quorum_refunds_every_commit() {
+        let ruling = dispute_with(&[(5, 2, 20)], 1, 10).tally(3);
+        assert_eq!(ruling.raiser_share, None);
+        assert_eq!(ruling.votes_revealed, 2);
+        assert_eq!(ruling.coherent_brackets, 0);
+        assert_eq!(ruling.reward_pool, 0);
+        assert_eq!(ruling.unclaimed_payouts, 3);
+        assert_eq!(ruling.unclaimed_amount, 30);
+    }
+
+    #[test]
+    fn needs_extension_counts_commits_up_to_the_extension_limit() {
+        let mut dispute = dispute_with(&[], 2, 10);
+        assert!(dispute.needs_extension(3, 2));
+        assert!(!dispute.needs_extension(2, 2));
+        dispute.extensions = 2;
+        assert!(!dispute.needs_extension(3, 2));
+    }
+
+    fn registered(juror: Pubkey) -> RegisteredJuror {
+        RegisteredJuror {
+            juror,
+            appeal_eligible: true,
+            suspended_until: 0,
+        }
+    }
+
+    #[test]
+    fn draw_panel_skips_parties_suspended_and_ineligible_jurors() {
+        let mut dispute = dispute_with(&[], 0, 10);
+        let mut suspended = registered(Pubkey::new_unique());
+        suspended.suspended_until = 1;
+        let mut junior = registered(Pubkey::new_unique());
+        junior.appeal_eligible = false;
+        let eligible = registered(Pubkey::new_unique());
+        let registry = vec![
+            registered(dispute.raiser),
+            registered(dispute.against),
+            suspended,
+            junior.clone(),
+            eligible.clone(),
+        ];
+        let drawn = dispute.draw_panel(&registry, 5, &[7; 32]);
+        assert_eq!(drawn.len(), 2);
+        assert!(drawn.contains(&junior.juror) && drawn.contains(&eligible.juror));
+        // Seated jurors are not drawn twice
+        assert!(dispute.draw_panel(&registry, 5, &[8; 32]).is_empty());
+
+        dispute.round = 2;
+        dispute.panel.clear();
+        assert_eq!(dispute.draw_panel(&registry, 5, &[7; 32]), vec![eligible.juror]);
+    }
+
+    #[test]
+    fn draw_panel_on_appeal_skips_first_round_jurors() {
+        let mut dispute = dispute_with(&[], 0, 10);
+        let registry: Vec<_> = (0..6).map(|_| registered(Pubkey::new_unique())).collect();
+        let first = dispute.draw_panel(&registry, 3, &[1; 32]);
+        assert_eq!(first.len(), 3);
+        dispute.round = 2;
+        dispute.prior_jurors.extend(dispute.panel.drain(..).map(|seat| seat.juror));
+        let appeal = dispute.draw_panel(&registry, 5, &[2; 32]);
+        assert_eq!(appeal.len(), 3);
+        assert!(appeal.iter().all(|juror| !first.contains(juror)));
+    }
+
+    #[test]
+    fn draw_panel_fills_up_to_the_seats() {
+        let mut dispute = dispute_with(&[], 0, 10);
+        let registry: Vec<_> = (0..MAX_PANEL_SIZE + 5)
+            .map(|_| registered(Pubkey::new_unique()))
+            .collect();
+        assert_eq!(dispute.draw_panel(&registry, 4, &[3; 32]).len(), 4);
+        assert_eq!(dispute.draw_panel(&registry, 6, &[4; 32]).len(), 2);
+        dispute.draw_panel(&registry, MAX_PANEL_SIZE + 5, &[5; 32]);
+        assert_eq!(dispute.panel.len(), MAX_PANEL_SIZE);
+    }
+
+    #[test]
+    fn commitment_for_binds_every_input() {
+        let (job, voter, salt) = (Pubkey::new_unique(), Pubkey::new_unique(), [9; 32]);
+        let commitment = VoteRecord::commitment_for(&job, &voter, 1, 7, &salt);
+        assert_eq!(commitment, VoteRecord::commitment_for(&job, &voter, 1, 7, &salt));
+        for other in [
+            VoteRecord::commitment_for(&Pubkey::new_unique(), &voter, 1, 7, &salt),
+            VoteRecord::commitment_for(&job, &Pubkey::new_unique(), 1, 7, &salt),
+            VoteRecord::commitment_for(&job, &voter, 2, 7, &salt),
+            VoteRecord::commitment_for(&job, &voter, 1, 6, &salt),
+            VoteRecord::commitment_for(&job, &voter, 1, 7, &[8; 32]),
+        ] {
+            assert_ne!(commitment, other);
+        }
+    }
+
+    #[test]
+    fn appeal_bond_is_refunded_only_when_the_appeal_improved_the_share() {
+        let (raiser, against) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let appeal = |appellant| Appeal {
+            appellant,
+            bond: 10,
+            created_at: 0,
+        };
+        assert_eq!(appeal(raiser).bond_recipient(raiser, against, 30, 60), (raiser, true));
+        assert_eq!(appeal(raiser).bond_recipient(raiser, against, 30, 30), (against, false));
+        assert_eq!(appeal(raiser).bond_recipient(raiser, against, 30, 20), (against, false));
+        assert_eq!(appeal(against).bond_recipient(raiser, against, 70, 40), (against, true));
+        assert_eq!(appeal(against).bond_recipient(raiser, against, 70, 70), (raiser, false));
+    }
 }